@@ -10,10 +10,10 @@
 //! ### Feature Toggles
 //! - `literal` - literal parsing
 //! - `util` - pre-made types and utility traits
-//! - `syn` - syn support via `Syn<impl syn::Parse>`
+//! - `syn` - syn support via `Syn<impl syn::Parse>` (and `SynStr<impl syn::Parse>` for string literals)
 //! 
 //! ### Examples
-//! ```
+//! ```ignore
 //! use kefta::{Attr, parse_attr};
 //!
 //! // derive `Attr` onto your struct
@@ -58,9 +58,9 @@
 //!     // parse with `syn`
 //!     let input = syn::parse_macro_input!(item as DeriveInput);
 //!
-//!     // parse the attributes with the `parse_attr!` macro
+//!     // parse the `#[human(...)]` attributes with the `parse_attr!` macro
 //!     // it contains a `return TokenStream`, so you don't have to handle errors.
-//!     let attrs = parse_attr!(input.attrs => MyAttrs);
+//!     let attrs = parse_attr!(input.attrs, "human" => MyAttrs);
 //!
 //!     // print out one of our fields
 //!     println!("Name:  {:?}", attrs.name);
@@ -77,15 +77,28 @@
 //! #[human(jobs="foo", jobs="bar", jobs="baz")]
 //! pub struct Jimmy;
 //! ```
+//!
+//! Attribute macros can parse their arguments with `AttrArgs`
+//! ```ignore
+//! #[proc_macro_attribute]
+//! pub fn human(args: TokenStream, item: TokenStream) -> TokenStream {
+//!     // parse the `name="Jimmy", value=10` of `#[human(name="Jimmy", value=10)]`
+//!     let attrs = parse_attr!(kefta::AttrArgs::new(args) => MyAttrs);
+//!
+//!     item
+//! }
+//! ```
 
 
 mod parse;
 
 pub use kefta_core::error;
 pub use kefta_core::token;
-pub use kefta_core::parse::{AttrValue};
+pub use kefta_core::parse::{AttrValue, AttrVariant};
 pub use kefta_core::node::{AttrNode, AttrTree};
-pub use kefta_core::structs::{AttrMap, AttrStruct, AttrParse};
+pub use kefta_core::structs::{AttrArgs, AttrDuplicates, AttrFlatten, AttrKeys, AttrMap, AttrStruct, AttrParse};
+#[cfg(feature = "syn")]
+pub use kefta_core::structs::AttrParseNamed;
 #[cfg(any(feature = "syn", feature = "util"))]
 pub use kefta_core::util;
 
@@ -100,11 +113,79 @@ pub use kefta_core::util;
 /// #[attr(optional)]               explicit use of an optional value (`Option<T>`)
 /// #[attr(multiple)]               parse multiple nodes (`Vec<T>`)
 /// #[attr(container)]              parse an inner structure (`impl AttrStruct`)
+/// #[attr(container, multiple)]    parse each container as its own structure (`Vec<T: AttrStruct>`)
 ///
 /// #[attr(name="name")]            rename the field
 /// #[attr(alias="b", alias="b")]   add an alias for the field
+/// #[attr(position=0)]             parse a positional (unnamed) value, e.g. `#[route("/path")]`
+/// #[attr(root_value)]             parse the value of `#[human = value]` (or a bare `#[human]` for `bool` fields)
+///
+/// #[attr(with=path::to)]          parse all nodes with a function, `fn(Vec<AttrNode>) -> KeftaResult<T>`
+/// #[attr(parse_with=path::to)]    parse each node with a function, `fn(AttrNode) -> KeftaResult<T>`
+///                                 (can be used with `required`, `optional`, `multiple` and defaults)
+///
+/// #[attr(default=8080)]           use a value if not present (instead of `Default`)
+/// #[attr(default_with=path::to)]  call a function if not present
 ///
-/// #[attr(with="path_to_func")]    parse the value with a function
+/// #[attr(skip)]                   never parse the field, using its default
+/// #[attr(compute=path::to)]       compute the field from the others, `fn(&Self) -> KeftaResult<T>`
+///
+/// #[attr(flatten)]                parse an inner structure from the same keys (`impl AttrFlatten`)
+/// #[attr(rest)]                   take all nodes not used by other fields (`Vec<AttrNode>`, `AttrMap`, `BTreeMap<String, T>`, `HashMap<String, T>`)
+///
+/// #[attr(bound="T: AttrValue")]   replace the inferred bounds of the field
+/// #[attr(duplicates="last")]      take the `first` or `last` of repeated keys, instead of an `error`
 /// ```
 ///
+/// computed fields are set after all other fields are parsed, in order of declaration.
+/// until then they hold their default, so earlier computed fields are visible to later ones.
+///
+/// the fields of tuple structs are parsed from positional values, in order (skipping any `skip` or `compute` fields).
+///
+/// lists (`Vec<T>`, `[T; N]` and tuples) are parsed from a single value,
+/// e.g. `tags=["a", "b"]`, `tags("a", "b")` or `size=(800, 600)`, unlike `multiple` which takes repeated keys.
+///
+/// maps (`BTreeMap<String, T>` and `HashMap<String, T>`) are parsed from a container keyed by its idents,
+/// e.g. `env(RUST_LOG="info", PORT=80)`, where a repeated key is an error.
+///
+/// derived structs also implement `AttrFlatten`, so they can be flattened into others.
+/// a key used by both a structure and a flattened field is a compile error, naming the key.
+/// this is caught by `cargo check` for non-generic structures,
+/// but only by a full build (once a structure is parsed) for generic structures.
+/// structures with a `rest` field cannot be flattened, as they would take the keys of their parent.
+///
+/// unknown keys are reported as errors once all fields are parsed,
+/// this can be disabled on the struct (along with other struct-level options)
+///
+/// ```text
+/// #[attr(allow_unknown)]          ignore unknown keys
+/// #[attr(deny_unknown)]           report unknown keys (the default)
+/// #[attr(bound="T: AttrValue")]   replace all inferred bounds
+/// #[attr(crate="path::to::kefta")] the path to `kefta` used in generated code (`::kefta` by default)
+/// #[attr(rename_all="kebab-case")] change the case of field keys (`name` takes precedence)
+/// #[attr(duplicates="last")]      the duplicate policy of all fields (`error` by default)
+/// ```
+///
+/// a single value given more than once (including under different aliases) is an error by default.
+///
+/// for generic items, bounds are inferred from the fields using type parameters,
+/// e.g. `T: AttrValue` for parsed values, and `Default` for values that fall back to it.
+///
+/// when derived on an enum of unit variants, `AttrValue` is implemented instead.
+/// values are matched against the variant name (ignoring ascii case),
+/// as an identifier or string literal (`mode=fast` or `mode="fast"`)
+///
+/// ```text
+/// #[attr(name="name")]            rename the variant
+/// #[attr(alias="b", alias="b")]   add an alias for the variant
+/// ```
+///
+/// enums with non-unit variants implement `AttrStruct`, selecting exactly one variant by key
+/// (the variant name in `snake_case`, e.g. `#[db(postgres(url="..."))]`).
+/// on enums, `rename_all` changes the case of the variant names.
+/// keys may be hyphenated, e.g. `max-size=10` for `kebab-case`.
+/// - unit variants are parsed as markers
+/// - single unnamed fields are parsed as an inner structure (`impl AttrStruct`)
+/// - named fields are parsed as the fields of a struct
+///
 pub use kefta_macro::Attr;
\ No newline at end of file
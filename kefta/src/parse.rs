@@ -1,10 +1,19 @@
 #[macro_export]
 /// parse an array of attributes
+///
+/// attributes can be filtered by name (requires the `syn` feature),
+/// e.g. `parse_attr!(input.attrs, "human" => MyAttrs)`
 macro_rules! parse_attr {
+    ($expr:expr, $($name:literal),+ => $type:ty) => {
+        match $crate::AttrParseNamed::parse_attrs_named_any::<$type>({ $expr }, &[$($name),+]) {
+            ::core::result::Result::Ok(value) => value,
+            ::core::result::Result::Err(error) => return error.to_compile_error().into(),
+        }
+    };
     ($expr:expr => $type:ty) => {
-        match kefta::AttrParse::parse_attrs::<$type>({ $expr }) {
-            Ok(value) => value,
-            Err(error) => return error.to_compile_error().into(),
+        match $crate::AttrParse::parse_attrs::<$type>({ $expr }) {
+            ::core::result::Result::Ok(value) => value,
+            ::core::result::Result::Err(error) => return error.to_compile_error().into(),
         }
     };
 }
\ No newline at end of file
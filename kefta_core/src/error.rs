@@ -2,6 +2,7 @@
 
 use std::fmt::{Debug, Formatter};
 use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use crate::node::ROOT_KEY;
 
 /// alias for `Result<T, KeftaError>`
 pub type KeftaResult<T> = Result<T, KeftaError>;
@@ -51,10 +52,51 @@ pub enum KeftaError {
         span: Span,
     },
 
+    /// found more than one node for a single value, possibly under different aliases.
+    ///
+    /// holds the span and key of the first and second nodes
+    Duplicate {
+        first: (Span, String),
+        second: (Span, String),
+    },
+
     /// the node is required, but was not found.
     Required {
         key: String,
         multiple: bool,
+        /// the span and key of a similarly named node, that may be a misspelling
+        similar: Option<(Span, String)>,
+        /// the span of the enclosing container, if known
+        span: Option<Span>,
+    },
+
+    /// none of the variants were found.
+    MissingVariant {
+        expected: Vec<String>,
+        /// the span of the enclosing container, if known
+        span: Option<Span>,
+    },
+
+    /// found more than one variant, but only expected one.
+    ConflictingVariants {
+        first: String,
+        second: String,
+        span: Span,
+    },
+
+    /// found nodes that were not expected.
+    Unknown {
+        /// the span and key of each node
+        keys: Vec<(Span, String)>,
+        /// the keys that were expected, used for suggestions
+        known: Vec<String>,
+    },
+
+    /// the value does not name a known variant.
+    UnknownVariant {
+        found: String,
+        expected: Vec<String>,
+        span: Span,
     },
 
     /// a generic message
@@ -63,6 +105,9 @@ pub enum KeftaError {
         span: Option<Span>,
     },
 
+    /// multiple errors, see `KeftaErrors`
+    Errors(Vec<KeftaError>),
+
     /// an error for `syn` compatibility
     #[cfg(feature = "syn")]
     Syn(syn::Error)
@@ -83,6 +128,8 @@ pub enum KeftaExpected {
     ByteLiteral,
     NumericLiteral,
     BooleanLiteral,
+
+    List,
 }
 
 
@@ -114,11 +161,37 @@ impl Debug for KeftaError {
                 f.debug_tuple("ExpectedMultiple")
                     .field(key)
                     .finish(),
+            KeftaError::Duplicate { first, second } =>
+                f.debug_tuple("Duplicate")
+                    .field(first)
+                    .field(second)
+                    .finish(),
             KeftaError::Required { key, .. } =>
                 f.debug_tuple("RequiredAttr")
                     .field(key)
                     .finish(),
-            KeftaError::Message { message, .. } => Debug::fmt(&message, f)
+            KeftaError::MissingVariant { expected, .. } =>
+                f.debug_tuple("MissingVariant")
+                    .field(expected)
+                    .finish(),
+            KeftaError::ConflictingVariants { first, second, .. } =>
+                f.debug_tuple("ConflictingVariants")
+                    .field(first)
+                    .field(second)
+                    .finish(),
+            KeftaError::Unknown { keys, .. } =>
+                f.debug_tuple("Unknown")
+                    .field(keys)
+                    .finish(),
+            KeftaError::UnknownVariant { found, .. } =>
+                f.debug_tuple("UnknownVariant")
+                    .field(found)
+                    .finish(),
+            KeftaError::Message { message, .. } => Debug::fmt(&message, f),
+            KeftaError::Errors(errors) =>
+                f.debug_list()
+                    .entries(errors)
+                    .finish(),
         }
     }
 }
@@ -138,10 +211,10 @@ impl KeftaError {
                         expected,
                         match description {
                             None => ",".to_string(),
-                            Some(desc) => format!(" [{}]", desc.to_string()),
+                            Some(desc) => format!(" [{}]", desc),
                         },
                         match found {
-                            TokenTree::Ident(ident) => format!("ident `{}`", ident.to_string()),
+                            TokenTree::Ident(ident) => format!("ident `{}`", ident),
                             TokenTree::Punct(punct) => format!("a `{}` token", punct.as_char()),
                             TokenTree::Literal(literal) => format!("literal `{}`", literal),
                             TokenTree::Group(group) => format!("a `{}` group", delimiter_str(group.delimiter())),
@@ -170,7 +243,8 @@ impl KeftaError {
                         KeftaExpected::StringLiteral => "a string literal",
                         KeftaExpected::ByteLiteral => "a byte-string literal (b\"\")",
                         KeftaExpected::NumericLiteral => "a numeric literal",
-                        KeftaExpected::BooleanLiteral => "a boolean literal (true/false)"
+                        KeftaExpected::BooleanLiteral => "a boolean literal (true/false)",
+                        KeftaExpected::List => "a list ([...] or (...))",
                     }
                 )),
 
@@ -181,60 +255,258 @@ impl KeftaError {
                     key
                 )),
 
-            KeftaError::Required { key, .. } =>
-                (Span::call_site(), format!(
-                    "the attribute `{}` is required", key
+            KeftaError::Duplicate { first, second } =>
+                (second.0, duplicate_message(&first.1, &second.1)),
+
+            KeftaError::Required { key, similar, span, .. } =>
+                match (positional_index(&key), similar) {
+                    (Some(index), _) =>
+                        (span.unwrap_or_else(Span::call_site), format!("the positional argument {} is required", index)),
+                    (None, Some((span, similar))) =>
+                        (span, format!(
+                            "the attribute `{}` is required, did you mean `{}` instead of `{}`?",
+                            key, key, similar
+                        )),
+                    (None, None) =>
+                        (span.unwrap_or_else(Span::call_site), format!("the attribute `{}` is required", key)),
+                },
+
+            KeftaError::MissingVariant { expected, span } =>
+                (span.unwrap_or_else(Span::call_site), format!(
+                    "expected one of {}",
+                    expected.iter()
+                        .map(|x| format!("`{}`", x))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )),
+
+            KeftaError::ConflictingVariants { first, second, span } =>
+                (span, format!(
+                    "`{}` cannot be used together with `{}`",
+                    second,
+                    first
+                )),
+
+            KeftaError::Unknown { keys, known } => {
+                let mut errors = keys.iter().map(|(span, key)| unknown_error(*span, key, &known));
+                let (span, message) = errors.next()
+                    .unwrap_or_else(|| (Span::call_site(), "unknown error".to_string()));
+                match errors.count() {
+                    0 => (span, message),
+                    more => (span, format!("{} (and {} more)", message, more)),
+                }
+            },
+
+            KeftaError::UnknownVariant { found, expected, span } =>
+                (span, format!(
+                    "unknown value `{}`, expected one of {}",
+                    found,
+                    expected.iter()
+                        .map(|x| format!("`{}`", x))
+                        .collect::<Vec<_>>()
+                        .join(", ")
                 )),
 
             KeftaError::Message { message, span } =>
-                (span.unwrap_or_else(|| Span::call_site()), message),
+                (span.unwrap_or_else(Span::call_site), message),
+
+            KeftaError::Errors(errors) => {
+                let mut errors = errors.into_iter().flat_map(KeftaError::build_all);
+                let (span, message) = errors.next()
+                    .unwrap_or_else(|| (Span::call_site(), "unknown error".to_string()));
+                match errors.count() {
+                    0 => (span, message),
+                    more => (span, format!("{} (and {} more)", message, more)),
+                }
+            },
 
             //this @ _ => syn::Error::new(Span::call_site(), format!("{:?}", this))
         }
     }
 
+    /// set the span of errors without one (e.g. `KeftaError::Required`),
+    /// such as errors from within a container.
+    pub fn within(self, span: Span) -> Self {
+        match self {
+            KeftaError::Required { key, multiple, similar, span: None } =>
+                KeftaError::Required { key, multiple, similar, span: Some(span) },
+            KeftaError::MissingVariant { expected, span: None } =>
+                KeftaError::MissingVariant { expected, span: Some(span) },
+            KeftaError::Message { message, span: None } =>
+                KeftaError::Message { message, span: Some(span) },
+            KeftaError::Errors(errors) =>
+                KeftaError::Errors(errors.into_iter().map(|error| error.within(span)).collect()),
+            error => error,
+        }
+    }
+
+    /// build every error message, as some errors may contain multiple spans
+    ///
+    /// this always builds at least one message, even for an empty `KeftaError::Errors`
+    pub fn build_all(self) -> Vec<(Span, String)> {
+        let built = match self {
+            KeftaError::Unknown { keys, known } =>
+                keys.iter().map(|(span, key)| unknown_error(*span, key, &known)).collect(),
+            KeftaError::Errors(errors) =>
+                errors.into_iter().flat_map(KeftaError::build_all).collect(),
+            KeftaError::Duplicate { first, second } => vec![
+                (second.0, duplicate_message(&first.1, &second.1)),
+                (first.0, format!("{} is first given here", describe_key(&first.1))),
+            ],
+            error => vec![error.build()]
+        };
+
+        if built.is_empty() {
+            vec![(Span::call_site(), "unknown error".to_string())]
+        } else {
+            built
+        }
+    }
+
     pub fn to_compile_error(self) -> TokenStream {
-        // from https://docs.rs/syn/latest/src/syn/error.rs.html#248
-        let (span, msg) = self.build();
-
-        // compile_error!($message)
-        TokenStream::from_iter(vec![
-            TokenTree::Ident(Ident::new("compile_error", span.clone())),
-            TokenTree::Punct({
-                let mut punct = Punct::new('!', Spacing::Alone);
-                punct.set_span(span.clone());
-                punct
-            }),
-            TokenTree::Group({
-                let mut group = Group::new(Delimiter::Brace, {
-                    TokenStream::from_iter(vec![TokenTree::Literal({
-                        let mut string = Literal::string(&msg);
-                        string.set_span(span.clone());
-                        string
-                    })])
-                });
-                group.set_span(span.clone());
-                group
-            }),
-        ])
+        TokenStream::from_iter(
+            self.build_all()
+                .into_iter()
+                .flat_map(|(span, msg)| compile_error(span, msg))
+        )
     }
 
     #[cfg(feature="syn")]
     pub fn into_syn(self) -> syn::Error {
         match self {
             KeftaError::Syn(e) => e,
-            e @ _ => {
-                let (span, msg) = e.build();
-                syn::Error::new(span, msg)
+            KeftaError::Errors(errors) if !errors.is_empty() =>
+                combine_syn(errors.into_iter().map(KeftaError::into_syn)),
+            e => combine_syn(
+                e.build_all()
+                    .into_iter()
+                    .map(|(span, msg)| syn::Error::new(span, msg))
+            )
+        }
+    }
+}
+
+#[cfg(feature="syn")]
+fn combine_syn(errors: impl Iterator<Item = syn::Error>) -> syn::Error {
+    errors
+        .reduce(|mut error, next| {
+            error.combine(next);
+            error
+        })
+        .unwrap_or_else(|| syn::Error::new(Span::call_site(), "unknown error"))
+}
+
+/// a collection of errors, for reporting every error instead of the first.
+#[derive(Debug, Default)]
+pub struct KeftaErrors {
+    errors: Vec<KeftaError>
+}
+
+impl KeftaErrors {
+    pub fn new() -> Self {
+        Self { errors: Vec::new() }
+    }
+
+    /// add an error to the collection
+    pub fn push(&mut self, error: KeftaError) {
+        match error {
+            KeftaError::Errors(errors) => self.errors.extend(errors),
+            error => self.errors.push(error)
+        }
+    }
+
+    /// take the value of a result, adding the error to the collection if failed.
+    pub fn ok<T>(&mut self, result: KeftaResult<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.push(error);
+                None
             }
         }
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// return `Ok` if no errors were collected,
+    /// otherwise a single error, or `KeftaError::Errors`
+    pub fn finish(mut self) -> KeftaResult<()> {
+        match self.errors.len() {
+            0 => Ok(()),
+            1 => Err(self.errors.remove(0)),
+            _ => Err(KeftaError::Errors(self.errors))
+        }
+    }
+
+    /// build a `compile_error!` invocation for every error
+    pub fn to_compile_error(self) -> TokenStream {
+        TokenStream::from_iter(
+            self.errors.into_iter().map(KeftaError::to_compile_error)
+        )
+    }
+
+    /// combine every error into a single `syn::Error`,
+    /// returning `None` if no errors were collected.
+    #[cfg(feature="syn")]
+    pub fn into_syn(self) -> Option<syn::Error> {
+        match self.finish() {
+            Ok(()) => None,
+            Err(error) => Some(error.into_syn())
+        }
+    }
+}
+
+impl Extend<KeftaError> for KeftaErrors {
+    fn extend<T: IntoIterator<Item=KeftaError>>(&mut self, iter: T) {
+        for error in iter {
+            self.push(error);
+        }
+    }
+}
+
+impl IntoIterator for KeftaErrors {
+    type Item = KeftaError;
+    type IntoIter = std::vec::IntoIter<KeftaError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+fn compile_error(span: Span, msg: String) -> TokenStream {
+    // from https://docs.rs/syn/latest/src/syn/error.rs.html#248
+    // compile_error!($message)
+    TokenStream::from_iter(vec![
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct({
+            let mut punct = Punct::new('!', Spacing::Alone);
+            punct.set_span(span);
+            punct
+        }),
+        TokenTree::Group({
+            let mut group = Group::new(Delimiter::Brace, {
+                TokenStream::from_iter(vec![TokenTree::Literal({
+                    let mut string = Literal::string(&msg);
+                    string.set_span(span);
+                    string
+                })])
+            });
+            group.set_span(span);
+            group
+        }),
+    ])
 }
 
 #[cfg(feature="syn")]
-impl Into<syn::Error> for KeftaError {
-    fn into(self) -> syn::Error {
-        self.into_syn()
+impl From<KeftaError> for syn::Error {
+    fn from(val: KeftaError) -> Self {
+        val.into_syn()
     }
 }
 
@@ -246,4 +518,186 @@ fn delimiter_str(delimiter: Delimiter) -> &'static str {
         Delimiter::Bracket => "[]",
         Delimiter::None => "_",
     }
-}
\ No newline at end of file
+}
+
+fn positional_index(key: &str) -> Option<usize> {
+    key.strip_prefix('_')?.parse().ok()
+}
+
+// describe a node key in a message, e.g. `foo` or positional argument 0
+fn describe_key(key: &str) -> String {
+    match positional_index(key) {
+        Some(index) => format!("positional argument {}", index),
+        None if key == ROOT_KEY => "the attribute value".to_string(),
+        None => format!("`{}`", key),
+    }
+}
+
+fn duplicate_message(first: &str, second: &str) -> String {
+    if first == second {
+        format!("duplicate {}", describe_key(second))
+    } else {
+        format!("duplicate {}, already given as {}", describe_key(second), describe_key(first))
+    }
+}
+
+fn unknown_error(span: Span, key: &str, known: &[String]) -> (Span, String) {
+    if positional_index(key).is_some() {
+        return (span, "unexpected positional argument".to_string());
+    }
+    if key == ROOT_KEY {
+        return (span, "unexpected attribute value, expected `(...)`".to_string());
+    }
+
+    match suggest(key, known.iter().map(String::as_str)) {
+        Some(similar) => (span, format!("unknown attribute `{}`, did you mean `{}`?", key, similar)),
+        None => (span, format!("unknown attribute `{}`", key)),
+    }
+}
+
+/// find the candidate most similar to a value, for "did you mean" suggestions.
+///
+/// candidates are compared by edit distance (counting transpositions),
+/// returning `None` if no candidate is close enough.
+pub fn suggest<'a>(value: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let mut best: Option<(usize, &'a str)> = None;
+
+    for candidate in candidates {
+        if candidate == value || candidate == ROOT_KEY || positional_index(candidate).is_some() {
+            continue;
+        }
+
+        let limit = (value.chars().count().max(candidate.chars().count()) / 3).max(1);
+        let distance = edit_distance(value, candidate);
+
+        let closer = match best {
+            Some((best, _)) => distance < best,
+            None => true,
+        };
+
+        if distance <= limit && closer {
+            best = Some((distance, candidate));
+        }
+    }
+
+    best.map(|(_, candidate)| candidate)
+}
+
+// optimal string alignment distance
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in table.iter_mut().enumerate() { row[0] = i; }
+    for (j, cell) in table[0].iter_mut().enumerate() { *cell = j; }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+
+            table[i][j] = (table[i - 1][j] + 1)
+                .min(table[i][j - 1] + 1)
+                .min(table[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                table[i][j] = table[i][j].min(table[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    table[a.len()][b.len()]
+}
+#[cfg(test)]
+mod tests {
+    use super::{edit_distance, suggest, KeftaError};
+    use crate::node::AttrNode;
+    use crate::structs::{AttrKeys, AttrMap};
+    use crate::token::AttrTokenStream;
+
+    fn known_map(input: &str, keys: &'static AttrKeys) -> AttrMap {
+        let nodes: Vec<AttrNode> = AttrTokenStream::new(input.parse().unwrap()).parse().unwrap();
+        let mut map = AttrMap::new(nodes);
+        map.know_keys(keys);
+        map
+    }
+
+    // the suggestion of a `KeftaError::Required` error
+    fn required_similar(error: KeftaError) -> Option<String> {
+        match error {
+            KeftaError::Required { similar, .. } => similar.map(|(_, key)| key),
+            error => panic!("expected a required error, found {:?}", error),
+        }
+    }
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("colour", "colour"), 0);
+        assert_eq!(edit_distance("colour", "color"), 1);
+        assert_eq!(edit_distance("colour", "colours"), 1);
+        assert_eq!(edit_distance("colour", "colout"), 1);
+        // a transposition is a single edit
+        assert_eq!(edit_distance("colour", "coluor"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn suggest_closest() {
+        assert_eq!(suggest("colr", ["size", "colour", "color"]), Some("color"));
+        assert_eq!(suggest("sise", ["size", "side"]), Some("size"));
+        assert_eq!(suggest("zzz", ["size", "colour"]), None);
+    }
+
+    #[test]
+    fn suggest_limit() {
+        // a third of the longest length, at least one
+        assert_eq!(suggest("ab", ["ac"]), Some("ac"));
+        assert_eq!(suggest("ab", ["cd"]), None);
+        assert_eq!(suggest("abcdef", ["abcxyf"]), Some("abcxyf"));
+        assert_eq!(suggest("abcdef", ["axcxyf"]), None);
+    }
+
+    #[test]
+    fn suggest_skips() {
+        // exact matches, positional and root keys are never suggested
+        assert_eq!(suggest("size", ["size"]), None);
+        assert_eq!(suggest("_1", ["_0"]), None);
+        assert_eq!(suggest("_roots", ["_root"]), None);
+    }
+
+    #[test]
+    fn required_suggests_unknown() {
+        static KEYS: AttrKeys = AttrKeys::new(&["colour", "size"], &[]);
+        let mut map = known_map("colr = 1, sise = 2", &KEYS);
+
+        assert!(map.parse_optional::<u8>(&["size"]).unwrap().is_none());
+        let error = map.parse_required::<u8>(&["colour"]).unwrap_err();
+        assert_eq!(required_similar(error).as_deref(), Some("colr"));
+
+        // the suggested key is not reported again
+        match map.deny_unknown() {
+            Err(KeftaError::Unknown { keys, .. }) =>
+                assert_eq!(keys.iter().map(|(_, key)| key.as_str()).collect::<Vec<_>>(), ["sise"]),
+            result => panic!("expected an unknown error, found {:?}", result),
+        }
+    }
+
+    #[test]
+    fn required_skips_known() {
+        // `sort` is used by a later field
+        static PORT_SORT: AttrKeys = AttrKeys::new(&["port", "sort"], &[]);
+        let mut map = known_map("sort", &PORT_SORT);
+        let error = map.parse_required::<u8>(&["port"]).unwrap_err();
+        assert_eq!(required_similar(error), None);
+        assert!(map.parse_one::<bool>(&["sort"]).unwrap());
+        assert!(map.deny_unknown().is_ok());
+
+        // `nme` is a field of its own
+        static NAME_NME: AttrKeys = AttrKeys::new(&["name", "nme"], &[]);
+        let mut map = known_map("nme = 1", &NAME_NME);
+        let error = map.parse_required::<u8>(&["name"]).unwrap_err();
+        assert_eq!(required_similar(error), None);
+        assert_eq!(map.parse_required::<u8>(&["nme"]).unwrap(), 1);
+        assert!(map.deny_unknown().is_ok());
+    }
+}
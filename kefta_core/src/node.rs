@@ -1,14 +1,35 @@
-use proc_macro2::{Delimiter, Ident, Punct, TokenTree};
+use proc_macro2::{Delimiter, Ident, Punct, TokenStream};
+
+/// the ident given to root attribute nodes, e.g. `#[attr = value]` or a bare `#[attr]`
+pub const ROOT_KEY: &str = "_root";
 
 /// an attribute node
 ///
-/// contains the `ident` and `data` of an attribute node
+/// contains the `ident`, `key` and `data` of an attribute node.
+/// positional nodes are given the ident `_0`, `_1`, ... in order of occurrence
+#[derive(Debug, Clone)]
 pub struct AttrNode {
     pub ident: Ident,
+    /// the key of the node, either the ident or hyphenated idents (e.g. `max-size`)
+    ///
+    /// for hyphenated keys, `ident` is the first of the idents
+    pub key: String,
     pub data: AttrTree
 }
 
+impl AttrNode {
+    /// create a new node, keyed by its ident
+    pub fn new(ident: Ident, data: AttrTree) -> Self {
+        Self {
+            key: ident.to_string(),
+            ident,
+            data
+        }
+    }
+}
+
 /// data of an attribute node
+#[derive(Debug, Clone)]
 pub enum AttrTree {
     /// a non-valued attribute
     ///
@@ -16,14 +37,25 @@ pub enum AttrTree {
     Marker,
     /// a valued attribute
     ///
-    /// has a given value as a `TokenTree`
+    /// has a given value as a `TokenStream`, up to the next top-level comma
     ///
-    /// e.g. `#[attr(foo=10)]` or `#[attr(foo=(10 + 10))]`
+    /// e.g. `#[attr(foo=10)]`, `#[attr(foo=(10 + 10))]` or `#[attr(foo=HashMap<K, V>)]`
     Valued {
         /// equal token
         equal: Punct,
         /// value token(s)
-        value: TokenTree,
+        value: TokenStream,
+    },
+    /// a positional (unnamed) attribute
+    ///
+    /// has a given value as a `TokenStream`, up to the next top-level comma
+    ///
+    /// e.g. `#[attr("foo")]` or `#[attr(10, foo=10)]`
+    Positional {
+        /// position of the node
+        index: usize,
+        /// value token(s)
+        value: TokenStream,
     },
     /// a container attribute
     ///
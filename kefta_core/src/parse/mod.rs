@@ -1,4 +1,6 @@
 mod traits;
 mod values;
+mod variant;
 
-pub use traits::*;
\ No newline at end of file
+pub use traits::*;
+pub use variant::AttrVariant;
\ No newline at end of file
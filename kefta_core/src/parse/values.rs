@@ -1,7 +1,9 @@
-use proc_macro2::{Span, TokenTree};
-use crate::error::{KeftaError, KeftaExpected, KeftaResult};
+use proc_macro2::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
+use crate::error::{KeftaError, KeftaErrors, KeftaExpected, KeftaResult};
 use crate::node::{AttrNode, AttrTree};
 use crate::parse::AttrValue;
+use crate::structs::AttrStruct;
+use crate::token::AttrTokenStream;
 
 // ---- primitive parsing ----
 impl AttrValue for () {
@@ -19,26 +21,199 @@ impl<T: AttrValue> AttrValue for Option<T> {
     }
 }
 
+// ---- node ----
+
+impl AttrValue for AttrNode {
+    fn parse(node: AttrNode) -> KeftaResult<Self> {
+        Ok(node)
+    }
+}
+
 // ---- token tree ----
 
-impl AttrValue for TokenTree {
+impl AttrValue for TokenStream {
     fn parse(node: AttrNode) -> KeftaResult<Self> {
         match node.data {
-            AttrTree::Valued { value, .. } => Ok(value),
+            AttrTree::Valued { value, .. }
+            | AttrTree::Positional { value, .. } => Ok(value),
             _ => Err(KeftaError::ExpectedValue { ident: node.ident }),
         }
     }
 }
 
+/// parses a value as a single token tree.
+/// values of multiple tokens are given as a group with `Delimiter::None`.
+impl AttrValue for TokenTree {
+    fn parse(node: AttrNode) -> KeftaResult<Self> {
+        let value = TokenStream::parse(node)?;
+
+        let mut iter = value.clone().into_iter();
+        match (iter.next(), iter.next()) {
+            (Some(token_tree), None) => Ok(token_tree),
+            (first, _) => {
+                let mut group = Group::new(Delimiter::None, value);
+                if let Some(first) = first {
+                    group.set_span(first.span());
+                }
+                Ok(TokenTree::Group(group))
+            }
+        }
+    }
+}
+
 impl AttrValue for (Span, TokenTree) {
     fn parse(node: AttrNode) -> KeftaResult<Self> {
-        match node.data {
-            AttrTree::Valued { value, .. } => Ok((value.span(), value)),
-            _ => Err(KeftaError::ExpectedValue { ident: node.ident }),
+        let value = TokenTree::parse(node)?;
+        Ok((value.span(), value))
+    }
+}
+
+// ---- list parsing ----
+
+/// parses a list of values, e.g. `[a, b]`, `(a, b)` or `key(a, b)`.
+/// byte-string literals (`b"ab"`) are parsed as a list of bytes.
+impl<T: AttrValue> AttrValue for Vec<T> {
+    fn parse(node: AttrNode) -> KeftaResult<Self> {
+        let (_, nodes) = list_nodes(node)?;
+        <Vec<T> as AttrStruct>::parse(nodes)
+    }
+}
+
+impl<T: AttrValue, const N: usize> AttrValue for [T; N] {
+    fn parse(node: AttrNode) -> KeftaResult<Self> {
+        let (span, nodes) = list_nodes(node)?;
+        if nodes.len() != N {
+            return Err(length_error(N, nodes.len(), span));
+        }
+
+        <Vec<T> as AttrStruct>::parse(nodes)?
+            .try_into()
+            .map_err(|values: Vec<T>| length_error(N, values.len(), span))
+    }
+}
+
+macro_rules! attr_tuple {
+    ( $( ( $( $type:ident ),+ ) ),* ) => {
+        $(
+            impl<$( $type: AttrValue ),+> AttrValue for ( $( $type, )+ ) {
+                #[allow(non_snake_case)]
+                fn parse(node: AttrNode) -> KeftaResult<Self> {
+                    let (span, nodes) = list_nodes(node)?;
+                    let len = [$( stringify!($type) ),+].len();
+                    if nodes.len() != len {
+                        return Err(length_error(len, nodes.len(), span));
+                    }
+
+                    let mut nodes = nodes.into_iter();
+                    let mut errors = KeftaErrors::new();
+                    let ( $( $type, )+ ) = ( $( errors.ok(<$type as AttrValue>::parse(nodes.next().unwrap())), )+ );
+
+                    errors.finish()?;
+                    Ok(( $( $type.unwrap(), )+ ))
+                }
+            }
+        )*
+    };
+}
+attr_tuple!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L)
+);
+
+// parse the elements of a list as positional nodes, with the span of the list
+fn list_nodes(node: AttrNode) -> KeftaResult<(Span, Vec<AttrNode>)> {
+    match node.data {
+        AttrTree::Container { nodes, .. } => {
+            let mut build = Vec::with_capacity(nodes.len());
+            let mut errors = KeftaErrors::new();
+
+            for (index, node) in nodes.into_iter().enumerate() {
+                match node.data {
+                    // idents within a container are parsed as markers, so are taken as values
+                    AttrTree::Marker if node.ident == node.key =>
+                        build.push(positional_node(index, node.ident.span(), TokenTree::Ident(node.ident).into())),
+                    AttrTree::Positional { .. } => build.push(node),
+
+                    // named values would be silently dropped, e.g. `size(width = 800)`
+                    _ => errors.push(KeftaError::Message {
+                        message: format!("expected a list element, found `{}`", node.key),
+                        span: Some(node.ident.span()),
+                    }),
+                }
+            }
+
+            errors.finish()?;
+            Ok((node.ident.span(), build))
+        },
+
+        AttrTree::Marker => Err(KeftaError::ExpectedValue { ident: node.ident }),
+
+        _ => match TokenTree::parse(node)? {
+            TokenTree::Group(group) if matches!(group.delimiter(), Delimiter::Bracket | Delimiter::Parenthesis) => {
+                let mut stream = AttrTokenStream::new_spanned(group.stream(), group.span());
+                let mut nodes = Vec::new();
+
+                while stream.has_tokens() {
+                    nodes.push(AttrNode::parse_positional(&mut stream, nodes.len()).map_err(KeftaError::TokenError)?);
+                    // values are taken up to the next comma
+                    stream.skip();
+                }
+
+                Ok((group.span(), nodes))
+            },
+
+            #[cfg(feature="literal")]
+            TokenTree::Literal(literal) => {
+                let span = literal.span();
+                match litrs::OwnedLiteral::from(literal) {
+                    litrs::Literal::ByteString(string) => Ok((
+                        span,
+                        string.value()
+                            .iter()
+                            .enumerate()
+                            .map(|(index, byte)| {
+                                let mut literal = proc_macro2::Literal::u8_unsuffixed(*byte);
+                                literal.set_span(span);
+                                positional_node(index, span, TokenTree::Literal(literal).into())
+                            })
+                            .collect()
+                    )),
+                    _ => Err(KeftaError::Expected { expected: KeftaExpected::List, span })
+                }
+            },
+
+            token_tree => Err(KeftaError::Expected {
+                expected: KeftaExpected::List,
+                span: token_tree.span(),
+            })
         }
     }
 }
 
+fn positional_node(index: usize, span: Span, value: TokenStream) -> AttrNode {
+    AttrNode::new(
+        Ident::new(&format!("_{}", index), span),
+        AttrTree::Positional { index, value },
+    )
+}
+
+fn length_error(expected: usize, found: usize, span: Span) -> KeftaError {
+    KeftaError::Message {
+        message: format!("expected {} values, found {}", expected, found),
+        span: Some(span),
+    }
+}
+
 // ---- tree parsing ----
 macro_rules! attr_tree {
     ($($ident: ident),*) => {
@@ -47,7 +222,7 @@ macro_rules! attr_tree {
                 fn parse(node: AttrNode) -> KeftaResult<Self> {
                     match TokenTree::parse(node)? {
                         TokenTree::$ident(x) => Ok(x),
-                        token_tree @ _ => Err(KeftaError::Expected {
+                        token_tree => Err(KeftaError::Expected {
                             expected: KeftaExpected::$ident,
                             span: token_tree.span(),
                         })
@@ -103,40 +278,89 @@ impl AttrValue for String {
 }
 
 #[cfg(feature="literal")]
-impl AttrValue for Vec<u8> {
-    fn parse(node: AttrNode) -> KeftaResult<Self> {
-        match <(Span, litrs::OwnedLiteral) as AttrValue>::parse(node)? {
-            (_, litrs::Literal::ByteString(string)) => Ok(string.into_value().to_vec()),
-            (span, _) => Err(KeftaError::Expected {
-                expected: KeftaExpected::ByteLiteral,
-                span
-            })
-        }
-    }
+macro_rules! attr_num {
+    ( $( $type:ty ),* ) => {
+        $(
+            impl AttrValue for $type {
+                fn parse(node: AttrNode) -> KeftaResult<Self> {
+                    let type_name = std::any::type_name::<$type>();
+
+                    match signed_literal(node)? {
+                        (span, false, litrs::Literal::Integer(integer)) => match integer.value::<$type>() {
+                            Some(num) => Ok(num),
+                            None => Err(overflow_error(&integer.to_string(), type_name, span)),
+                        },
+                        (span, true, litrs::Literal::Integer(integer)) => {
+                            let number = format!("-{}", integer);
+
+                            // the magnitude of `i128::MIN` does not fit in `i128`
+                            let value = match integer.value::<u128>() {
+                                Some(magnitude) if magnitude <= i128::MIN.unsigned_abs() =>
+                                    (magnitude as i128).wrapping_neg(),
+                                _ => return Err(overflow_error(&number, type_name, span)),
+                            };
+
+                            match <$type>::try_from(value) {
+                                Ok(num) => Ok(num),
+                                Err(_) if <$type>::MIN == 0 => Err(KeftaError::Message {
+                                    message: format!("negative number `{}` cannot be used for unsigned type `{}`", number, type_name),
+                                    span: Some(span)
+                                }),
+                                Err(_) => Err(overflow_error(&number, type_name, span)),
+                            }
+                        },
+                        (span, negative, litrs::Literal::Float(float)) => Err(KeftaError::Message {
+                            message: format!(
+                                "expected an integer for type `{}`, found float `{}{}`",
+                                type_name,
+                                if negative { "-" } else { "" },
+                                float.to_string()
+                            ),
+                            span: Some(span)
+                        }),
+                        (span, _, _) => Err(KeftaError::Expected {
+                            expected: KeftaExpected::NumericLiteral,
+                            span
+                        })
+                    }
+                }
+            }
+        )*
+    };
 }
+#[cfg(feature="literal")]
+attr_num!(usize, u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, isize);
 
 #[cfg(feature="literal")]
-macro_rules! attr_num {
+macro_rules! attr_float {
     ( $( $type:ty ),* ) => {
         $(
             impl AttrValue for $type {
                 fn parse(node: AttrNode) -> KeftaResult<Self> {
-                    match <(Span, litrs::OwnedLiteral) as AttrValue>::parse(node)? {
-                        (span, litrs::Literal::Integer(integer)) => match integer.value::<$type>() {
-                            Some(num) => Ok(num),
-                            None => Err(KeftaError::Message {
-                                message: format!(
-                                    "integer `{}` overflows type `{}`",
-                                    integer.to_string(),
-                                    std::any::type_name::<$type>()
-                                ),
-                                span: Some(span)
-                            })
+                    let (span, negative, number) = match signed_literal(node)? {
+                        (span, negative, litrs::Literal::Float(float)) =>
+                            (span, negative, float.number_part().replace('_', "")),
+                        (span, negative, litrs::Literal::Integer(integer)) => match integer.value::<u128>() {
+                            Some(num) => (span, negative, num.to_string()),
+                            None => return Err(overflow_error(
+                                &format!("{}{}", if negative { "-" } else { "" }, integer),
+                                std::any::type_name::<$type>(),
+                                span
+                            )),
                         },
-                        (span, _) => Err(KeftaError::Expected {
+                        (span, _, _) => return Err(KeftaError::Expected {
                             expected: KeftaExpected::NumericLiteral,
                             span
                         })
+                    };
+
+                    match number.parse::<$type>() {
+                        Ok(num) if num.is_finite() => Ok(if negative { -num } else { num }),
+                        _ => Err(overflow_error(
+                            &format!("{}{}", if negative { "-" } else { "" }, number),
+                            std::any::type_name::<$type>(),
+                            span
+                        )),
                     }
                 }
             }
@@ -144,21 +368,98 @@ macro_rules! attr_num {
     };
 }
 #[cfg(feature="literal")]
-attr_num!(usize, u8, i8, u16, i16, u32, i32, u64, i64);
+attr_float!(f32, f64);
+
+#[cfg(feature="literal")]
+macro_rules! attr_non_zero {
+    ( $( $type:ident => $inner:ty ),* ) => {
+        $(
+            impl AttrValue for std::num::$type {
+                fn parse(node: AttrNode) -> KeftaResult<Self> {
+                    let span = value_span(&node);
+                    match std::num::$type::new(<$inner as AttrValue>::parse(node)?) {
+                        Some(num) => Ok(num),
+                        None => Err(KeftaError::Message {
+                            message: format!("expected a non-zero value for type `{}`", stringify!($type)),
+                            span: Some(span)
+                        })
+                    }
+                }
+            }
+        )*
+    };
+}
+#[cfg(feature="literal")]
+attr_non_zero!(
+    NonZeroUsize => usize, NonZeroU8 => u8, NonZeroU16 => u16, NonZeroU32 => u32, NonZeroU64 => u64, NonZeroU128 => u128,
+    NonZeroIsize => isize, NonZeroI8 => i8, NonZeroI16 => i16, NonZeroI32 => i32, NonZeroI64 => i64, NonZeroI128 => i128
+);
+
+// parse a literal with an optional leading sign (`-` or `+`),
+// returning the span of the value and if it is negative.
+#[cfg(feature="literal")]
+fn signed_literal(node: AttrNode) -> KeftaResult<(Span, bool, litrs::OwnedLiteral)> {
+    let mut value = TokenStream::parse(node)?;
+
+    // unwrap invisible groups, e.g. from `macro_rules` expressions
+    loop {
+        let mut iter = value.clone().into_iter();
+        match (iter.next(), iter.next()) {
+            (Some(TokenTree::Group(group)), None) if group.delimiter() == Delimiter::None =>
+                value = group.stream(),
+            _ => break,
+        }
+    }
+
+    let mut iter = value.into_iter();
+    let (negative, sign, literal) = match iter.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '-' => (true, Some(punct.span()), iter.next()),
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '+' => (false, Some(punct.span()), iter.next()),
+        token => (false, None, token),
+    };
+
+    match (literal, iter.next()) {
+        (Some(TokenTree::Literal(literal)), None) =>
+            Ok((sign.unwrap_or_else(|| literal.span()), negative, litrs::OwnedLiteral::from(literal))),
+        (token, _) => Err(KeftaError::Expected {
+            expected: KeftaExpected::NumericLiteral,
+            span: sign.or_else(|| token.map(|token| token.span())).unwrap_or_else(Span::call_site),
+        }),
+    }
+}
+
+// the span of the first value token, or the ident if not valued
+#[cfg(feature="literal")]
+fn value_span(node: &AttrNode) -> Span {
+    match &node.data {
+        AttrTree::Valued { value, .. } | AttrTree::Positional { value, .. } =>
+            value.clone().into_iter().next().map(|token| token.span()),
+        _ => None,
+    }.unwrap_or_else(|| node.ident.span())
+}
+
+#[cfg(feature="literal")]
+fn overflow_error(number: &str, type_name: &str, span: Span) -> KeftaError {
+    KeftaError::Message {
+        message: format!("number `{}` overflows type `{}`", number, type_name),
+        span: Some(span)
+    }
+}
 
 impl AttrValue for bool {
     fn parse(node: AttrNode) -> KeftaResult<Self> {
         match node.data {
             AttrTree::Marker => Ok(true),
 
-            #[cfg(feature="literal")]
-            AttrTree::Valued { .. } =>
-                match  <(Span, litrs::OwnedLiteral) as AttrValue>::parse(node)? {
-                    (_, litrs::Literal::Bool(boolean)) => Ok(boolean.value()),
+            // `true` and `false` are tokenized as idents
+            AttrTree::Valued { .. } | AttrTree::Positional { .. } =>
+                match TokenTree::parse(node)? {
+                    TokenTree::Ident(ident) if ident == "true" => Ok(true),
+                    TokenTree::Ident(ident) if ident == "false" => Ok(false),
 
-                    (span, _) => Err(KeftaError::Expected {
+                    token_tree => Err(KeftaError::Expected {
                         expected: KeftaExpected::BooleanLiteral,
-                        span
+                        span: token_tree.span()
                     })
                 },
 
@@ -166,3 +467,128 @@ impl AttrValue for bool {
         }
     }
 }
+
+#[cfg(all(test, feature="literal"))]
+mod tests {
+    use std::num::{NonZeroI8, NonZeroU8};
+    use proc_macro2::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+    use crate::error::KeftaResult;
+    use crate::node::{AttrNode, AttrTree};
+    use crate::parse::AttrValue;
+    use crate::token::AttrTokenStream;
+
+    // parse the value of `v = <value>`
+    fn parse<T: AttrValue>(value: &str) -> KeftaResult<T> {
+        let stream: TokenStream = format!("v = {}", value).parse().unwrap();
+        let mut nodes: Vec<AttrNode> = AttrTokenStream::new(stream).parse().unwrap();
+        T::parse(nodes.remove(0))
+    }
+
+    // parse the value of `v = <value>`, returning the error message
+    fn error<T: AttrValue + std::fmt::Debug>(value: &str) -> String {
+        parse::<T>(value).unwrap_err().build().1
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(parse::<u8>("255").unwrap(), 255);
+        assert_eq!(parse::<i8>("-128").unwrap(), -128);
+        assert_eq!(parse::<i32>("+3").unwrap(), 3);
+        assert_eq!(parse::<i32>("- 3").unwrap(), -3);
+        assert_eq!(parse::<isize>("-1_000").unwrap(), -1000);
+        assert_eq!(parse::<u64>("0x10").unwrap(), 16);
+        assert_eq!(parse::<u128>("340282366920938463463374607431768211455").unwrap(), u128::MAX);
+    }
+
+    #[test]
+    fn integer_limits() {
+        assert_eq!(parse::<i128>("-170141183460469231731687303715884105728").unwrap(), i128::MIN);
+        assert_eq!(parse::<i128>("170141183460469231731687303715884105727").unwrap(), i128::MAX);
+
+        assert_eq!(
+            error::<i128>("-170141183460469231731687303715884105729"),
+            "number `-170141183460469231731687303715884105729` overflows type `i128`"
+        );
+        assert_eq!(error::<i8>("-129"), "number `-129` overflows type `i8`");
+        assert_eq!(error::<i8>("128"), "number `128` overflows type `i8`");
+        assert_eq!(error::<u8>("256"), "number `256` overflows type `u8`");
+    }
+
+    #[test]
+    fn unsigned_negatives() {
+        assert_eq!(parse::<u8>("-0").unwrap(), 0);
+        assert_eq!(parse::<u32>("+0").unwrap(), 0);
+        assert_eq!(error::<u8>("-1"), "negative number `-1` cannot be used for unsigned type `u8`");
+        assert_eq!(error::<usize>("-5"), "negative number `-5` cannot be used for unsigned type `usize`");
+    }
+
+    #[test]
+    fn integer_errors() {
+        assert_eq!(error::<i32>("1.5"), "expected an integer for type `i32`, found float `1.5`");
+        assert_eq!(error::<i32>("-1.5"), "expected an integer for type `i32`, found float `-1.5`");
+        assert_eq!(error::<i32>("\"1\""), "expected `a numeric literal`");
+        assert_eq!(error::<i32>("-x"), "expected `a numeric literal`");
+        assert_eq!(error::<i32>("1 2"), "expected `a numeric literal`");
+    }
+
+    #[test]
+    fn invisible_groups() {
+        // e.g. `$value:expr` in `macro_rules`
+        let inner = TokenTree::Group(Group::new(Delimiter::None, "-5".parse().unwrap()));
+        let outer = TokenTree::Group(Group::new(Delimiter::None, inner.into()));
+        let node = AttrNode::new(
+            Ident::new("v", Span::call_site()),
+            AttrTree::Valued { equal: Punct::new('=', Spacing::Alone), value: outer.into() }
+        );
+
+        assert_eq!(i32::parse(node.clone()).unwrap(), -5);
+        assert_eq!(f64::parse(node).unwrap(), -5.0);
+    }
+
+    #[test]
+    fn lists() {
+        assert_eq!(parse::<Vec<String>>("[\"a\", \"b\",]").unwrap(), ["a", "b"]);
+        assert_eq!(parse::<(u32, u32)>("(800, 600)").unwrap(), (800, 600));
+        assert_eq!(parse::<[i8; 3]>("[-1, 2, 3]").unwrap(), [-1, 2, 3]);
+        assert_eq!(parse::<Vec<u8>>("b\"hi\"").unwrap(), b"hi");
+        assert_eq!(error::<[u8; 2]>("[1, 2, 3]"), "expected 2 values, found 3");
+
+        let stream: TokenStream = "tags(\"a\", b)".parse().unwrap();
+        let mut nodes: Vec<AttrNode> = AttrTokenStream::new(stream).parse().unwrap();
+        let tags = <Vec<TokenStream> as AttrValue>::parse(nodes.remove(0)).unwrap();
+        assert_eq!(tags.iter().map(|tag| tag.to_string()).collect::<Vec<_>>(), ["\"a\"", "b"]);
+    }
+
+    #[test]
+    fn list_named_elements() {
+        for (input, key) in [("size(width = 800, heigth = 600)", "width"), ("tags(a = \"x\")", "a"), ("tags(a(b))", "a"), ("tags(a-b)", "a-b")] {
+            let stream: TokenStream = input.parse().unwrap();
+            let mut nodes: Vec<AttrNode> = AttrTokenStream::new(stream).parse().unwrap();
+            let error = <Vec<TokenStream> as AttrValue>::parse(nodes.remove(0)).unwrap_err();
+            assert_eq!(error.build_all()[0].1, format!("expected a list element, found `{}`", key), "{}", input);
+        }
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(parse::<f32>("-1.5").unwrap(), -1.5);
+        assert_eq!(parse::<f64>("+2.5").unwrap(), 2.5);
+        assert_eq!(parse::<f64>("2").unwrap(), 2.0);
+        assert_eq!(parse::<f64>("1_000.5e1").unwrap(), 10005.0);
+        assert_eq!(parse::<f32>("1f32").unwrap(), 1.0);
+
+        assert_eq!(error::<f32>("1e40"), "number `1e40` overflows type `f32`");
+        assert_eq!(error::<f64>("-1e400"), "number `-1e400` overflows type `f64`");
+        assert_eq!(error::<f64>("x"), "expected `a numeric literal`");
+    }
+
+    #[test]
+    fn non_zero() {
+        assert_eq!(parse::<NonZeroU8>("5").unwrap().get(), 5);
+        assert_eq!(parse::<NonZeroI8>("-1").unwrap().get(), -1);
+
+        assert_eq!(error::<NonZeroU8>("0"), "expected a non-zero value for type `NonZeroU8`");
+        assert_eq!(error::<NonZeroI8>("-0"), "expected a non-zero value for type `NonZeroI8`");
+        assert_eq!(error::<NonZeroU8>("-1"), "negative number `-1` cannot be used for unsigned type `u8`");
+    }
+}
@@ -0,0 +1,64 @@
+use proc_macro2::{Span, TokenTree};
+use crate::error::{KeftaError, KeftaExpected, KeftaResult};
+use crate::node::{AttrNode, AttrTree};
+use crate::parse::AttrValue;
+
+/// the name of a unit variant, given as an identifier or string literal
+///
+/// e.g. `#[attr(mode=fast)]` or `#[attr(mode="fast")]`
+pub struct AttrVariant {
+    /// the given name
+    pub value: String,
+    /// the span of the value
+    pub span: Span,
+}
+
+impl AttrValue for AttrVariant {
+    fn parse(node: AttrNode) -> KeftaResult<Self> {
+        match node.data {
+            AttrTree::Valued { .. } | AttrTree::Positional { .. } => match TokenTree::parse(node)? {
+                TokenTree::Ident(ident) => Ok(Self {
+                    value: ident.to_string(),
+                    span: ident.span(),
+                }),
+
+                #[cfg(feature="literal")]
+                TokenTree::Literal(literal) => {
+                    let span = literal.span();
+                    match litrs::OwnedLiteral::from(literal) {
+                        litrs::Literal::String(string) => Ok(Self {
+                            value: string.value().to_string(),
+                            span,
+                        }),
+                        _ => Err(KeftaError::Expected {
+                            expected: KeftaExpected::StringLiteral,
+                            span
+                        })
+                    }
+                },
+
+                token_tree => Err(KeftaError::Expected {
+                    expected: KeftaExpected::Ident,
+                    span: token_tree.span(),
+                })
+            },
+            _ => Err(KeftaError::ExpectedValue { ident: node.ident })
+        }
+    }
+}
+
+impl AttrVariant {
+    /// check if the value matches any of the given names (ignoring ascii case)
+    pub fn is(&self, names: &[&str]) -> bool {
+        names.iter().any(|name| name.eq_ignore_ascii_case(&self.value))
+    }
+
+    /// build a `KeftaError::UnknownVariant` error, listing the accepted names
+    pub fn error(self, names: &[&str]) -> KeftaError {
+        KeftaError::UnknownVariant {
+            found: self.value,
+            expected: names.iter().map(|x| x.to_string()).collect(),
+            span: self.span,
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::error::KeftaResult;
+use crate::structs::{AttrMap, AttrStruct};
+
+/// a structure parsed from a shared `AttrMap`, allowing it to be flattened into another.
+///
+/// unlike `AttrStruct::parse`, unknown keys are left to the owner of the map.
+pub trait AttrFlatten: AttrStruct {
+    /// the keys used by the structure, checked for collisions when evaluated
+    const KEYS: AttrKeys;
+
+    fn parse_map(map: &mut AttrMap) -> KeftaResult<Self>;
+}
+
+/// the keys used by a structure and the structures flattened into it
+pub struct AttrKeys {
+    pub keys: &'static [&'static str],
+    pub flatten: &'static [&'static AttrKeys],
+    /// if the structure takes all remaining keys, so cannot be flattened
+    pub rest: bool,
+}
+
+impl AttrKeys {
+    /// create a new set of keys, panicking (at compile time if used as a constant)
+    /// if a key is used by more than one of the structures.
+    ///
+    /// also panics if a flattened structure takes the remaining keys (see `with_rest`),
+    /// as it would take the keys of the structure it is flattened into.
+    pub const fn new(keys: &'static [&'static str], flatten: &'static [&'static AttrKeys]) -> Self {
+        let this = Self { keys, flatten, rest: false };
+        if let Some(key) = this.collides(&this) {
+            collision_panic(key);
+        }
+
+        let mut i = 0;
+        while i < flatten.len() {
+            if flatten[i].rest {
+                panic!("a structure with a `rest` field cannot be flattened");
+            }
+            i += 1;
+        }
+        this
+    }
+
+    /// mark the structure as taking all remaining keys
+    pub const fn with_rest(self) -> Self {
+        Self { rest: true, ..self }
+    }
+
+    /// find any of own keys that appear elsewhere in the `root` tree
+    const fn collides(&self, root: &AttrKeys) -> Option<&'static str> {
+        let mut i = 0;
+        while i < self.keys.len() {
+            if root.count(self.keys[i]) > own_count(self.keys, self.keys[i]) {
+                return Some(self.keys[i]);
+            }
+            i += 1;
+        }
+
+        let mut i = 0;
+        while i < self.flatten.len() {
+            if let Some(key) = self.flatten[i].collides(root) {
+                return Some(key);
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// count the occurrences of a key in the tree
+    const fn count(&self, key: &str) -> usize {
+        let mut count = own_count(self.keys, key);
+
+        let mut i = 0;
+        while i < self.flatten.len() {
+            count += self.flatten[i].count(key);
+            i += 1;
+        }
+        count
+    }
+}
+
+// panic naming a colliding key, as constant panics cannot be formatted
+const fn collision_panic(key: &str) -> ! {
+    const PREFIX: &[u8] = b"attribute key `";
+    const SUFFIX: &[u8] = b"` is used by both a structure and a flattened field";
+
+    let key = key.as_bytes();
+    let mut buffer = [0u8; 256];
+    let mut len = 0;
+
+    let mut i = 0;
+    while i < PREFIX.len() {
+        buffer[len] = PREFIX[i];
+        len += 1;
+        i += 1;
+    }
+
+    let mut i = 0;
+    while i < key.len() && len < buffer.len() - SUFFIX.len() {
+        buffer[len] = key[i];
+        len += 1;
+        i += 1;
+    }
+
+    let mut i = 0;
+    while i < SUFFIX.len() {
+        buffer[len] = SUFFIX[i];
+        len += 1;
+        i += 1;
+    }
+
+    match std::str::from_utf8(buffer.split_at(len).0) {
+        Ok(message) => panic!("{}", message),
+        Err(_) => panic!("attribute key is used by both a structure and a flattened field"),
+    }
+}
+
+const fn own_count(keys: &[&str], key: &str) -> usize {
+    let mut count = 0;
+    let mut i = 0;
+    while i < keys.len() {
+        if str_eq(keys[i], key) {
+            count += 1;
+        }
+        i += 1;
+    }
+    count
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
@@ -1,33 +1,116 @@
 use std::collections::BTreeMap;
-use crate::error::{KeftaError, KeftaResult};
-use crate::node::{AttrNode, AttrTree};
-use crate::parse::AttrValue;
-use crate::structs::AttrStruct;
+use crate::error::{suggest, KeftaError, KeftaErrors, KeftaResult};
+use crate::node::{AttrNode, AttrTree, ROOT_KEY};
+use crate::parse::{AttrValue, AttrVariant};
+use crate::structs::{AttrKeys, AttrStruct};
 
 const _EMPTY: Vec<AttrNode> = Vec::new();
 const _EMPTY_REF: &Vec<AttrNode> = &_EMPTY;
 
+/// how a single value is taken when multiple nodes are found for its keys (including aliases)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AttrDuplicates {
+    /// return a `KeftaError::Duplicate` error
+    #[default]
+    Error,
+    /// take the first node
+    First,
+    /// take the last node
+    Last,
+}
+
+impl AttrValue for AttrDuplicates {
+    fn parse(node: AttrNode) -> KeftaResult<Self> {
+        let variant = <AttrVariant as AttrValue>::parse(node)?;
+
+        if variant.is(&["error"]) {
+            Ok(Self::Error)
+        } else if variant.is(&["first"]) {
+            Ok(Self::First)
+        } else if variant.is(&["last"]) {
+            Ok(Self::Last)
+        } else {
+            Err(variant.error(&["error", "first", "last"]))
+        }
+    }
+}
+
 /// map for parsing an array of attribute nodes
+#[derive(Debug, Clone)]
 pub struct AttrMap {
-    map: BTreeMap<String, Vec<AttrNode>>
+    map: BTreeMap<String, Vec<AttrNode>>,
+    // the position of each node, in order of occurrence
+    indices: BTreeMap<String, Vec<usize>>,
+    known: Vec<String>,
+    // keys already suggested in place of a required key
+    suggested: Vec<String>,
+    duplicates: AttrDuplicates,
 }
 
 impl AttrMap {
     /// Create a new `AttrMap` from an array of attribute nodes
     pub fn new(nodes: Vec<AttrNode>) -> Self {
-        let mut map: BTreeMap<String, Vec<AttrNode>> = BTreeMap::new();
+        let mut map = Self {
+            map: BTreeMap::new(),
+            indices: BTreeMap::new(),
+            known: Vec::new(),
+            suggested: Vec::new(),
+            duplicates: AttrDuplicates::default(),
+        };
 
-        for node in nodes {
-            let name = node.ident.to_string();
+        for (index, node) in nodes.into_iter().enumerate() {
+            map.insert(index, node);
+        }
 
-            if let Some(array) = map.get_mut(&name) {
-                array.push(node);
-            } else {
-                map.insert(name, vec![node]);
-            }
+        map
+    }
+
+    // insert a node at a given position
+    fn insert(&mut self, index: usize, node: AttrNode) {
+        let name = node.key.clone();
+        self.indices.entry(name.clone()).or_default().push(index);
+        self.map.entry(name).or_default().push(node);
+    }
+
+    // remove the nodes with a given key, with their positions
+    fn remove(&mut self, key: &str) -> Option<Vec<(usize, AttrNode)>> {
+        let nodes = self.map.remove(key)?;
+        let indices = self.indices.remove(key).unwrap_or_default();
+        Some(indices.into_iter().zip(nodes).collect())
+    }
+
+    /// set the duplicate policy used when taking single values
+    pub fn set_duplicates(&mut self, duplicates: AttrDuplicates) {
+        self.duplicates = duplicates;
+    }
+
+    /// run a function with a given duplicate policy, restoring the previous policy after.
+    pub fn with_duplicates<T>(&mut self, duplicates: AttrDuplicates, func: impl FnOnce(&mut Self) -> T) -> T {
+        let previous = std::mem::replace(&mut self.duplicates, duplicates);
+        let result = func(self);
+        self.duplicates = previous;
+        result
+    }
+
+    /// run a function with any bare root attributes (e.g. `#[attr]`) hidden,
+    /// so they are not taken as the value of a root field.
+    ///
+    /// the bare attributes are restored after, and are still ignored by `deny_unknown`.
+    pub fn without_bare_root<T>(&mut self, func: impl FnOnce(&mut Self) -> T) -> T {
+        let (bare, valued): (Vec<_>, Vec<_>) = self.remove(ROOT_KEY)
+            .into_iter()
+            .flatten()
+            .partition(|(_, node)| matches!(node.data, AttrTree::Marker));
+
+        for (index, node) in valued {
+            self.insert(index, node);
+        }
+        let result = func(self);
+        for (index, node) in bare {
+            self.insert(index, node);
         }
 
-        Self { map }
+        result
     }
 
     /// compare a list of keys/names and return the first occurring.
@@ -52,20 +135,61 @@ impl AttrMap {
         names[0]
     }
 
+    /// select a single variant from an array of key arrays,
+    /// returning the index of the variant present.
+    ///
+    /// returns a `KeftaError::MissingVariant` error if no variant is present,
+    /// or `KeftaError::ConflictingVariants` if more than one is present.
+    pub fn select(&self, variants: &[&[&str]]) -> KeftaResult<usize> {
+        // the first occurrence of each variant present, in order of occurrence
+        let mut present: Vec<(usize, usize, &str)> = Vec::new();
+
+        for (index, keys) in variants.iter().enumerate() {
+            let first = keys.iter()
+                .filter_map(|key| Some((self.first_index(key)?, *key)))
+                .min_by_key(|(position, _)| *position);
+
+            if let Some((position, key)) = first {
+                present.push((position, index, key));
+            }
+        }
+        present.sort_by_key(|(position, ..)| *position);
+
+        if let [(_, _, first), (_, _, second), ..] = present.as_slice() {
+            return Err(KeftaError::ConflictingVariants {
+                first: first.to_string(),
+                second: second.to_string(),
+                span: self.peek_nodes(second)[0].ident.span(),
+            });
+        }
+        let found = present.first().map(|(_, index, key)| (*index, *key));
+
+        match found {
+            Some((index, _)) => Ok(index),
+            None => Err(KeftaError::MissingVariant {
+                expected: variants.iter().map(|keys| keys[0].to_string()).collect(),
+                span: None,
+            })
+        }
+    }
+
+    // the position of the first node with a given key
+    fn first_index(&self, key: &str) -> Option<usize> {
+        self.indices.get(key)?.iter().copied().min()
+    }
+
     /// peek (without removing) the nodes with a given key
     pub fn peek_nodes(&self, key: &str) -> &Vec<AttrNode> {
         match self.map.get(key) {
-            None => &_EMPTY_REF,
-            Some(nodes) => &nodes
+            None => _EMPTY_REF,
+            Some(nodes) => nodes
         }
     }
 
     /// get (removing from map) the nodes with a given key
     pub fn get_nodes(&mut self, key: &str) -> Option<Vec<AttrNode>> {
-        match self.map.remove(key) {
-            None => None,
-            Some(nodes) => Some(nodes)
-        }
+        self.know(key);
+        self.remove(key).map(|nodes| nodes.into_iter().map(|(_, node)| node).collect())
     }
 
     /// peek (without removing) the first matching node with the given key
@@ -84,27 +208,58 @@ impl AttrMap {
     ///
     /// the parameter `error` controls duplicate behaviour
     /// - when set to `true` - if multiple with the same key are found,
-    ///  a `KeftaError::Multiple` error will be returned
-    /// - when set to `false` - multiple nodes will be ignored.
+    ///   a `KeftaError::Multiple` error will be returned
+    /// - when set to `false` - the duplicate policy of the map is followed, see `take_node`.
     pub fn get_node(&mut self, key: &str, error: bool) -> KeftaResult<Option<AttrNode>> {
-        match self.map.remove(key) {
+        if !error {
+            return self.take_node(&[key]);
+        }
+
+        match self.get_nodes(key) {
             None => Ok(None),
             Some(mut nodes) => if nodes.len() == 1 {
                 Ok(Some(nodes.remove(0)))
             } else {
-                if error {
-                    Err(KeftaError::Multiple {
-                        key: key.to_string(),
-                        count: nodes.len(),
-                        span: nodes.first().unwrap().ident.span()
-                    })
-                } else {
-                    Ok(None)
-                }
+                Err(KeftaError::Multiple {
+                    key: key.to_string(),
+                    count: nodes.len(),
+                    span: nodes.first().unwrap().ident.span()
+                })
             }
         }
     }
 
+    /// take (removing from map) a single node from an array of keys,
+    /// following the duplicate policy of the map if more than one is found.
+    pub fn take_node(&mut self, keys: &[&str]) -> KeftaResult<Option<AttrNode>> {
+        let mut found = Vec::new();
+        for key in keys {
+            self.know(key);
+            found.extend(self.remove(key).into_iter().flatten());
+        }
+        found.sort_by_key(|(index, _)| *index);
+
+        let mut found = found.into_iter().map(|(_, node)| node);
+        let first = found.next();
+        let mut rest = found.peekable();
+
+        if rest.peek().is_none() {
+            return Ok(first);
+        }
+
+        match self.duplicates {
+            AttrDuplicates::Error => {
+                let (first, second) = (first.unwrap(), rest.next().unwrap());
+                Err(KeftaError::Duplicate {
+                    first: (first.ident.span(), first.key),
+                    second: (second.ident.span(), second.key),
+                })
+            },
+            AttrDuplicates::First => Ok(first),
+            AttrDuplicates::Last => Ok(rest.last()),
+        }
+    }
+
     /// gather nodes (removing from map) with an array of keys
     pub fn gather_nodes(&mut self, keys: &[&str])  -> KeftaResult<Vec<AttrNode>> {
         let mut build = Vec::new();
@@ -120,75 +275,208 @@ impl AttrMap {
         Ok(build)
     }
 
+    /// take (removing from map) all remaining nodes, in order of occurrence.
+    ///
+    /// bare root attributes (e.g. `#[attr]`) are left in the map.
+    pub fn drain(&mut self) -> Vec<AttrNode> {
+        let mut build = Vec::new();
+
+        let keys: Vec<String> = self.map.keys().cloned().collect();
+        for key in keys {
+            for (index, node) in self.remove(&key).into_iter().flatten() {
+                if node.ident == ROOT_KEY && matches!(node.data, AttrTree::Marker) {
+                    self.insert(index, node);
+                } else {
+                    build.push((index, node));
+                }
+            }
+        }
+
+        build.sort_by_key(|(index, _)| *index);
+        build.into_iter().map(|(_, node)| node).collect()
+    }
+
+    // record a key as known, for suggestions
+    fn know(&mut self, key: &str) {
+        if !self.known.iter().any(|known| known == key) {
+            self.known.push(key.to_string());
+        }
+    }
+
+    /// record every key of a structure (and those flattened into it) as known,
+    /// so a node with one of these keys is never suggested in place of another key.
+    pub fn know_keys(&mut self, keys: &AttrKeys) {
+        for key in keys.keys {
+            self.know(key);
+        }
+        for flatten in keys.flatten {
+            self.know_keys(flatten);
+        }
+    }
+
+    /// build a `KeftaError::Required` error for an array of keys,
+    /// suggesting a similarly named node if present (and not a known key, see `know_keys`).
+    ///
+    /// a suggested node is not reported again by `deny_unknown`.
+    pub fn required_error(&mut self, keys: &[&str], multiple: bool) -> KeftaError {
+        let candidates = self.map.keys()
+            .map(String::as_str)
+            .filter(|candidate| !self.known.iter().any(|known| known == candidate));
+        let similar = keys.iter()
+            .find_map(|key| suggest(key, candidates.clone()))
+            .map(|similar| (self.map[similar][0].ident.span(), similar.to_string()));
+
+        if let Some((_, similar)) = &similar {
+            self.suggested.push(similar.clone());
+        }
+
+        KeftaError::Required {
+            key: keys[0].to_string(),
+            multiple,
+            similar,
+            span: None,
+        }
+    }
+
+    /// check that every node has been taken from the map,
+    /// returning a `KeftaError::Unknown` error for any remaining nodes.
+    ///
+    /// bare root attributes (e.g. `#[attr]`) and nodes already suggested by `required_error` are ignored.
+    pub fn deny_unknown(&self) -> KeftaResult<()> {
+        let keys: Vec<_> = self.map.iter()
+            .filter(|(key, _)| !self.suggested.contains(key))
+            .flat_map(|(_, nodes)| nodes)
+            .filter(|node| !(node.ident == ROOT_KEY && matches!(node.data, AttrTree::Marker)))
+            .map(|node| (node.ident.span(), node.key.clone()))
+            .collect();
+
+        if keys.is_empty() {
+            Ok(())
+        } else {
+            Err(KeftaError::Unknown { keys, known: self.known.clone() })
+        }
+    }
 
     /* parse functions */
 
     /// parse a single node from an array of keys,
     /// returning `Default::default()` if not present.
     pub fn parse_one<T: AttrValue + Default>(&mut self, keys: &[&str])  -> KeftaResult<T> {
-        for key in keys {
-            if let Some(node) = self.get_node(key, false)? {
-                return <T as AttrValue>::parse(node);
-            }
-        }
-        Ok(<T as Default>::default())
+        self.parse_or(keys, <T as Default>::default)
+    }
+
+    /// parse a single node from an array of keys,
+    /// returning the result of `default` if not present.
+    pub fn parse_or<T: AttrValue>(&mut self, keys: &[&str], default: impl FnOnce() -> T)  -> KeftaResult<T> {
+        self.parse_or_with(keys, <T as AttrValue>::parse, default)
     }
 
     /// parse an optional single node from an array of keys
     pub fn parse_optional<T: AttrValue>(&mut self, keys: &[&str])  -> KeftaResult<Option<T>> {
-        for key in keys {
-            if let Some(node) = self.get_node(key, false)? {
-                return <T as AttrValue>::parse(node).map(|x| Some(x));
-            }
-        }
-        Ok(None)
+        self.parse_optional_with(keys, <T as AttrValue>::parse)
     }
 
     /// parse an single node from an array of keys,
     /// returning an `KeftaError::Required` error if not present.
     pub fn parse_required<T: AttrValue>(&mut self, keys: &[&str])  -> KeftaResult<T> {
-        for key in keys {
-            if let Some(node) = self.get_node(key, false)? {
-                return <T as AttrValue>::parse(node);
-            }
-        }
-        Err(KeftaError::Required {
-            key: keys[0].to_string(),
-            multiple: false
-        })
+        self.parse_required_with(keys, <T as AttrValue>::parse)
     }
 
     /// parse an array of nodes, from an array of keys.
     pub fn parse_array<T: AttrValue>(&mut self, keys: &[&str])  -> KeftaResult<Vec<T>> {
+        self.parse_array_with(keys, <T as AttrValue>::parse)
+    }
+
+    /// parse an array of nodes, from an array of keys.
+    /// returns `None` if no matching nodes are found
+    pub fn parse_array_optional<T: AttrValue>(&mut self, keys: &[&str])  -> KeftaResult<Option<Vec<T>>> {
+        self.parse_array_optional_with(keys, <T as AttrValue>::parse)
+    }
+
+    /// parse an array of nodes, from an array of keys.
+    /// returns the result of `default` if no matching nodes are found
+    pub fn parse_array_or<T: AttrValue>(&mut self, keys: &[&str], default: impl FnOnce() -> Vec<T>)  -> KeftaResult<Vec<T>> {
+        self.parse_array_or_with(keys, <T as AttrValue>::parse, default)
+    }
+
+    /// parse an array of nodes, from an array of keys.
+    /// returns an `KeftaError::Required` error if no matching nodes are found
+    pub fn parse_array_required<T: AttrValue>(&mut self, keys: &[&str])  -> KeftaResult<Vec<T>> {
+        self.parse_array_required_with(keys, <T as AttrValue>::parse)
+    }
+
+    /* parse functions, with a given function per node */
+
+    /// parse a single node from an array of keys with a given function,
+    /// returning the result of `default` if not present.
+    pub fn parse_or_with<T>(
+        &mut self,
+        keys: &[&str],
+        func: impl FnOnce(AttrNode) -> KeftaResult<T>,
+        default: impl FnOnce() -> T
+    ) -> KeftaResult<T> {
+        Ok(self.parse_optional_with(keys, func)?.unwrap_or_else(default))
+    }
+
+    /// parse an optional single node from an array of keys with a given function
+    pub fn parse_optional_with<T>(&mut self, keys: &[&str], func: impl FnOnce(AttrNode) -> KeftaResult<T>) -> KeftaResult<Option<T>> {
+        match self.take_node(keys)? {
+            Some(node) => func(node).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// parse an single node from an array of keys with a given function,
+    /// returning an `KeftaError::Required` error if not present.
+    pub fn parse_required_with<T>(&mut self, keys: &[&str], func: impl FnOnce(AttrNode) -> KeftaResult<T>) -> KeftaResult<T> {
+        match self.parse_optional_with(keys, func)? {
+            Some(value) => Ok(value),
+            None => Err(self.required_error(keys, false)),
+        }
+    }
+
+    /// parse an array of nodes, from an array of keys with a given function.
+    pub fn parse_array_with<T>(&mut self, keys: &[&str], mut func: impl FnMut(AttrNode) -> KeftaResult<T>) -> KeftaResult<Vec<T>> {
         let mut build = Vec::new();
+        let mut errors = KeftaErrors::new();
 
         for key in keys {
             if let Some(nodes) = self.get_nodes(key) {
                 for node in nodes {
-                    build.push(T::parse(node)?);
+                    build.extend(errors.ok(func(node)));
                 }
             }
         }
 
+        errors.finish()?;
         Ok(build)
     }
 
-    /// parse an array of nodes, from an array of keys.
+    /// parse an array of nodes, from an array of keys with a given function.
     /// returns `None` if no matching nodes are found
-    pub fn parse_array_optional<T: AttrValue>(&mut self, keys: &[&str])  -> KeftaResult<Option<Vec<T>>> {
-        let array = self.parse_array(&keys)?;
+    pub fn parse_array_optional_with<T>(&mut self, keys: &[&str], func: impl FnMut(AttrNode) -> KeftaResult<T>) -> KeftaResult<Option<Vec<T>>> {
+        let array = self.parse_array_with(keys, func)?;
         if array.is_empty() { Ok(None) } else { Ok(Some(array)) }
     }
 
-    /// parse an array of nodes, from an array of keys.
+    /// parse an array of nodes, from an array of keys with a given function.
+    /// returns the result of `default` if no matching nodes are found
+    pub fn parse_array_or_with<T>(
+        &mut self,
+        keys: &[&str],
+        func: impl FnMut(AttrNode) -> KeftaResult<T>,
+        default: impl FnOnce() -> Vec<T>
+    ) -> KeftaResult<Vec<T>> {
+        let array = self.parse_array_with(keys, func)?;
+        if array.is_empty() { Ok(default()) } else { Ok(array) }
+    }
+
+    /// parse an array of nodes, from an array of keys with a given function.
     /// returns an `KeftaError::Required` error if no matching nodes are found
-    pub fn parse_array_required<T: AttrValue>(&mut self, keys: &[&str])  -> KeftaResult<Vec<T>> {
-        let array = self.parse_array(&keys)?;
+    pub fn parse_array_required_with<T>(&mut self, keys: &[&str], func: impl FnMut(AttrNode) -> KeftaResult<T>) -> KeftaResult<Vec<T>> {
+        let array = self.parse_array_with(keys, func)?;
         if array.is_empty() {
-            Err(KeftaError::Required {
-                key: keys[0].to_string(),
-                multiple: true
-            })
+            Err(self.required_error(keys, true))
         } else {
             Ok(array)
         }
@@ -201,7 +489,7 @@ impl AttrMap {
     pub fn parse_container<T: AttrStruct>(&mut self, keys: &[&str])  -> KeftaResult<T> {
         let mut build = Vec::new();
 
-        for node in self.gather_nodes(&keys)? {
+        for node in self.gather_nodes(keys)? {
             match node.data {
                 AttrTree::Container { nodes, .. } => build.extend(nodes),
                 _ => return Err(KeftaError::ExpectedContainer { ident: node.ident })
@@ -211,8 +499,138 @@ impl AttrMap {
         T::parse(build)
     }
 
+    /// parse an array of `AttrStruct` from an array of keys, one for each container.
+    ///
+    /// returns `KeftaError::ExpectedContainer` for any non-container found.
+    pub fn parse_container_array<T: AttrStruct>(&mut self, keys: &[&str]) -> KeftaResult<Vec<T>> {
+        let mut build = Vec::new();
+        let mut errors = KeftaErrors::new();
+
+        for node in self.gather_nodes(keys)? {
+            match node.data {
+                AttrTree::Container { nodes, .. } => {
+                    let span = node.ident.span();
+                    build.extend(errors.ok(T::parse(nodes).map_err(|error| error.within(span))));
+                },
+                _ => errors.push(KeftaError::ExpectedContainer { ident: node.ident })
+            }
+        }
+
+        errors.finish()?;
+        Ok(build)
+    }
+
     /// parse an array of nodes with a given function
     pub fn parse_with<T>(&mut self, keys: &[&str], func: fn(nodes: Vec<AttrNode>) -> KeftaResult<T>) -> KeftaResult<T> {
-        (func)(self.gather_nodes(&keys)?)
+        (func)(self.gather_nodes(keys)?)
+    }
+}
+
+impl AttrStruct for AttrMap {
+    fn parse(nodes: Vec<AttrNode>) -> KeftaResult<Self> {
+        Ok(AttrMap::new(nodes))
+    }
+}
+#[cfg(test)]
+mod tests {
+    use crate::error::KeftaError;
+    use crate::node::{AttrNode, AttrTree};
+    use crate::structs::{AttrDuplicates, AttrMap};
+    use crate::token::AttrTokenStream;
+
+    fn map(input: &str) -> AttrMap {
+        let nodes: Vec<AttrNode> = AttrTokenStream::new(input.parse().unwrap()).parse().unwrap();
+        AttrMap::new(nodes)
+    }
+
+    #[test]
+    fn select() {
+        let variants: &[&[&str]] = &[&["sqlite"], &["memory", "mem"]];
+
+        assert_eq!(map("sqlite(path = 1)").select(variants).unwrap(), 0);
+        assert_eq!(map("mem").select(variants).unwrap(), 1);
+        assert!(matches!(map("").select(variants), Err(KeftaError::MissingVariant { .. })));
+    }
+
+    #[test]
+    fn select_conflict_in_source_order() {
+        let variants: &[&[&str]] = &[&["sqlite"], &["memory", "mem"]];
+
+        match map("memory, sqlite(path = 1)").select(variants) {
+            Err(KeftaError::ConflictingVariants { first, second, .. }) =>
+                assert_eq!((first.as_str(), second.as_str()), ("memory", "sqlite")),
+            result => panic!("expected a conflict, found {:?}", result),
+        }
+
+        match map("sqlite(path = 1), mem").select(variants) {
+            Err(KeftaError::ConflictingVariants { first, second, .. }) =>
+                assert_eq!((first.as_str(), second.as_str()), ("sqlite", "mem")),
+            result => panic!("expected a conflict, found {:?}", result),
+        }
+    }
+
+    // the value of a taken node
+    fn value(node: Option<AttrNode>) -> Option<String> {
+        node.map(|node| match node.data {
+            AttrTree::Valued { value, .. } => value.to_string(),
+            data => panic!("expected a value, found {:?}", data),
+        })
+    }
+
+    #[test]
+    fn take_single() {
+        let mut map = map("b = 1, c = 2");
+        assert_eq!(value(map.take_node(&["a", "b"]).unwrap()).as_deref(), Some("1"));
+        assert_eq!(value(map.take_node(&["a", "b"]).unwrap()), None);
+    }
+
+    #[test]
+    fn take_duplicate_error() {
+        for (input, first, second) in [("b = 1, a = 2", "b", "a"), ("a = 2, b = 1", "a", "b"), ("a = 1, a = 2", "a", "a")] {
+            match map(input).take_node(&["a", "b"]) {
+                Err(KeftaError::Duplicate { first: (_, found_first), second: (_, found_second) }) =>
+                    assert_eq!((found_first.as_str(), found_second.as_str()), (first, second), "{}", input),
+                result => panic!("expected a duplicate error for `{}`, found {:?}", input, result),
+            }
+        }
+
+        // both spans are reported
+        let messages = map("b = 1, a = 2").take_node(&["a", "b"]).unwrap_err().build_all();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].1, "duplicate `a`, already given as `b`");
+        assert_eq!(messages[1].1, "`b` is first given here");
+    }
+
+    #[test]
+    fn take_duplicate_first_last() {
+        for (input, first, last) in [("b = 1, a = 2", "1", "2"), ("a = 2, b = 1", "2", "1"), ("a = 1, c = 0, b = 2, a = 3", "1", "3")] {
+            let mut first_map = map(input);
+            first_map.set_duplicates(AttrDuplicates::First);
+            assert_eq!(value(first_map.take_node(&["a", "b"]).unwrap()).as_deref(), Some(first), "{}", input);
+            // the other duplicates are taken, so not unknown
+            assert_eq!(first_map.deny_unknown().is_ok(), !input.contains("c ="), "{}", input);
+
+            let mut last_map = map(input);
+            last_map.set_duplicates(AttrDuplicates::Last);
+            assert_eq!(value(last_map.take_node(&["a", "b"]).unwrap()).as_deref(), Some(last), "{}", input);
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn with_duplicates_restores() {
+        let mut map = map("a = 1, a = 2, b = 3, b = 4");
+        let first = map.with_duplicates(AttrDuplicates::Last, |map| map.take_node(&["a"]));
+        assert_eq!(value(first.unwrap()).as_deref(), Some("2"));
+        assert!(matches!(map.take_node(&["b"]), Err(KeftaError::Duplicate { .. })));
+    }
+
+    #[test]
+    fn get_node_follows_policy() {
+        assert!(matches!(map("a = 1, a = 2").get_node("a", false), Err(KeftaError::Duplicate { .. })));
+        assert!(matches!(map("a = 1, a = 2").get_node("a", true), Err(KeftaError::Multiple { .. })));
+
+        let mut map = map("a = 1, a = 2");
+        map.set_duplicates(AttrDuplicates::Last);
+        assert_eq!(value(map.get_node("a", false).unwrap()).as_deref(), Some("2"));
+    }
+}
@@ -1,7 +1,11 @@
 mod traits;
 mod map;
 mod parse;
+mod flatten;
 
 pub use traits::*;
-pub use map::AttrMap;
-pub use parse::AttrParse;
\ No newline at end of file
+pub use map::{AttrDuplicates, AttrMap};
+pub use flatten::{AttrFlatten, AttrKeys};
+pub use parse::{AttrArgs, AttrParse};
+#[cfg(feature = "syn")]
+pub use parse::AttrParseNamed;
\ No newline at end of file
@@ -1,4 +1,4 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use crate::error::{KeftaError, KeftaResult};
 use crate::node::AttrNode;
 use crate::structs::AttrStruct;
@@ -20,6 +20,40 @@ impl AttrParse for TokenStream {
     }
 }
 
+/// the arguments of an attribute macro, e.g. `foo, bar = 1` for `#[my_macro(foo, bar = 1)]`
+///
+/// unlike `TokenStream`, the nodes are not wrapped in a group.
+pub struct AttrArgs {
+    tokens: TokenStream,
+    span: Span,
+}
+
+impl AttrArgs {
+    pub fn new(tokens: impl Into<TokenStream>) -> Self {
+        Self {
+            tokens: tokens.into(),
+            span: Span::call_site(),
+        }
+    }
+
+    /// use the given span for errors when the arguments are empty
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+}
+
+impl AttrParse for AttrArgs {
+    fn parse_attrs<T: AttrStruct>(self) -> KeftaResult<T> {
+        let mut stream = AttrTokenStream::new_spanned(self.tokens, self.span);
+        let nodes = match stream.parse::<Vec<AttrNode>>() {
+            Ok(parse) => parse,
+            Err(token) => return Err(KeftaError::TokenError(token))
+        };
+        T::parse(nodes)
+    }
+}
+
 impl AttrParse for Vec<TokenStream> {
     fn parse_attrs<T: AttrStruct>(self) -> KeftaResult<T> {
         let mut nodes = Vec::new();
@@ -56,3 +90,44 @@ impl AttrParse for Vec<syn::Attribute> {
         tokens.parse_attrs()
     }
 }
+
+/// parse attributes with a given path into a given struct,
+/// ignoring any other attributes (such as `#[doc]` or `#[derive]`)
+#[cfg(feature = "syn")]
+pub trait AttrParseNamed {
+    /// parse the attributes with the given name, e.g. `human` for `#[human(...)]`
+    fn parse_attrs_named<T: AttrStruct>(self, name: &str) -> KeftaResult<T>;
+
+    /// parse the attributes matching any of the given names
+    fn parse_attrs_named_any<T: AttrStruct>(self, names: &[&str]) -> KeftaResult<T>;
+}
+
+#[cfg(feature = "syn")]
+impl AttrParseNamed for Vec<syn::Attribute> {
+    fn parse_attrs_named<T: AttrStruct>(self, name: &str) -> KeftaResult<T> {
+        self.parse_attrs_named_any(&[name])
+    }
+
+    fn parse_attrs_named_any<T: AttrStruct>(self, names: &[&str]) -> KeftaResult<T> {
+        self.as_slice().parse_attrs_named_any(names)
+    }
+}
+
+#[cfg(feature = "syn")]
+impl AttrParseNamed for &[syn::Attribute] {
+    fn parse_attrs_named<T: AttrStruct>(self, name: &str) -> KeftaResult<T> {
+        self.parse_attrs_named_any(&[name])
+    }
+
+    fn parse_attrs_named_any<T: AttrStruct>(self, names: &[&str]) -> KeftaResult<T> {
+        let mut tokens = Vec::new();
+
+        for attr in self {
+            if names.iter().any(|name| attr.path.is_ident(name)) {
+                tokens.push(attr.tokens.clone());
+            }
+        }
+
+        tokens.parse_attrs()
+    }
+}
\ No newline at end of file
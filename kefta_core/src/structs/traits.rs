@@ -1,5 +1,8 @@
-use crate::error::KeftaResult;
-use crate::node::AttrNode;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasher;
+use proc_macro2::Span;
+use crate::error::{KeftaError, KeftaErrors, KeftaResult};
+use crate::node::{AttrNode, AttrTree, ROOT_KEY};
 use crate::parse::AttrValue;
 
 /// a defined structure of attributes
@@ -10,11 +13,135 @@ pub trait AttrStruct: Sized {
 impl<T: AttrValue> AttrStruct for Vec<T> {
     fn parse(nodes: Vec<AttrNode>) -> KeftaResult<Self> {
         let mut build = Vec::with_capacity(nodes.len());
+        let mut errors = KeftaErrors::new();
 
         for node in nodes {
-            build.push(<T as AttrValue>::parse(node)?);
+            build.extend(errors.ok(<T as AttrValue>::parse(node)));
         }
 
+        errors.finish()?;
         Ok(build)
     }
-}
\ No newline at end of file
+}
+impl<T: AttrValue> AttrStruct for BTreeMap<String, T> {
+    fn parse(nodes: Vec<AttrNode>) -> KeftaResult<Self> {
+        parse_map(nodes)
+    }
+}
+
+impl<T: AttrValue, S: BuildHasher + Default> AttrStruct for HashMap<String, T, S> {
+    fn parse(nodes: Vec<AttrNode>) -> KeftaResult<Self> {
+        parse_map(nodes)
+    }
+}
+
+/// parses a map keyed by the idents of a container, e.g. `env(RUST_LOG = "info", PORT = 80)`
+impl<T: AttrValue> AttrValue for BTreeMap<String, T> {
+    fn parse(node: AttrNode) -> KeftaResult<Self> {
+        parse_map(container_nodes(node)?)
+    }
+}
+
+/// parses a map keyed by the idents of a container, e.g. `env(RUST_LOG = "info", PORT = 80)`
+impl<T: AttrValue, S: BuildHasher + Default> AttrValue for HashMap<String, T, S> {
+    fn parse(node: AttrNode) -> KeftaResult<Self> {
+        parse_map(container_nodes(node)?)
+    }
+}
+
+// parse each node by its key, reporting any repeated keys
+fn parse_map<T: AttrValue, M: Default + Extend<(String, T)>>(nodes: Vec<AttrNode>) -> KeftaResult<M> {
+    let mut keys: BTreeMap<String, Span> = BTreeMap::new();
+    let mut build = M::default();
+    let mut errors = KeftaErrors::new();
+
+    for node in nodes {
+        // only real keys are used, not the idents given to unnamed nodes
+        if let AttrTree::Positional { index, .. } = node.data {
+            errors.push(KeftaError::Message {
+                message: format!("expected a named value, found positional argument {}", index),
+                span: Some(node.ident.span()),
+            });
+            continue;
+        }
+        if node.key == ROOT_KEY {
+            errors.push(KeftaError::Message {
+                message: "expected a named value, found the attribute value".to_string(),
+                span: Some(node.ident.span()),
+            });
+            continue;
+        }
+
+        let key = node.key.clone();
+
+        if let Some(first) = keys.get(&key) {
+            errors.push(KeftaError::Duplicate {
+                first: (*first, key),
+                second: (node.ident.span(), node.key),
+            });
+            continue;
+        }
+        keys.insert(key.clone(), node.ident.span());
+
+        if let Some(value) = errors.ok(<T as AttrValue>::parse(node)) {
+            build.extend(Some((key, value)));
+        }
+    }
+
+    errors.finish()?;
+    Ok(build)
+}
+
+fn container_nodes(node: AttrNode) -> KeftaResult<Vec<AttrNode>> {
+    match node.data {
+        AttrTree::Container { nodes, .. } => Ok(nodes),
+        _ => Err(KeftaError::ExpectedContainer { ident: node.ident })
+    }
+}
+
+#[cfg(all(test, feature="literal"))]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use crate::error::KeftaResult;
+    use crate::node::AttrNode;
+    use crate::parse::AttrValue;
+    use crate::token::AttrTokenStream;
+
+    fn parse<T: AttrValue>(input: &str) -> KeftaResult<T> {
+        let mut nodes: Vec<AttrNode> = AttrTokenStream::new(input.parse().unwrap()).parse().unwrap();
+        T::parse(nodes.remove(0))
+    }
+
+    // the error messages of a result
+    fn messages<T>(result: KeftaResult<T>) -> Vec<String> {
+        match result {
+            Ok(_) => panic!("expected an error"),
+            Err(error) => error.build_all().into_iter().map(|(_, message)| message).collect(),
+        }
+    }
+
+    #[test]
+    fn map_from_container() {
+        let map: BTreeMap<String, u16> = parse("env(PORT = 80, max-size = 5)").unwrap();
+        assert_eq!(map, BTreeMap::from([("PORT".to_string(), 80), ("max-size".to_string(), 5)]));
+
+        let map: HashMap<String, bool> = parse("flags(a, b = false)").unwrap();
+        assert_eq!(map, HashMap::from([("a".to_string(), true), ("b".to_string(), false)]));
+    }
+
+    #[test]
+    fn map_errors() {
+        assert_eq!(
+            messages(parse::<BTreeMap<String, u8>>("env(a = 1, a = 2)")),
+            ["duplicate `a`", "`a` is first given here"]
+        );
+        assert_eq!(
+            messages(parse::<BTreeMap<String, String>>("env(\"x\", \"y\")")),
+            ["expected a named value, found positional argument 0", "expected a named value, found positional argument 1"]
+        );
+        assert_eq!(
+            messages(parse::<BTreeMap<String, u8>>("env = 1")),
+            ["expected a `container` attribute"]
+        );
+    }
+}
@@ -1,16 +1,35 @@
-use proc_macro2::{Delimiter, Group, TokenTree};
+use proc_macro2::{Delimiter, Ident, Spacing, TokenStream, TokenTree};
 use crate::error::KeftaTokenError;
-use crate::node::{AttrNode, AttrTree};
+use crate::node::{AttrNode, AttrTree, ROOT_KEY};
 use crate::token::{AttrTokenParse, AttrTokenStream};
 
-const NODE_EXPECTED: &'static str = "`=`, `,`, `(...)`";
+const NODE_EXPECTED: &str = "`=`, `,`, `(...)`";
 
 // parse node
 impl AttrTokenParse for AttrNode {
     fn parse(stream: &mut AttrTokenStream) -> Result<Self, KeftaTokenError> {
+        // node ident
+        let ident: Ident = stream.parse()?;
+        let mut key = ident.to_string();
+
+        // hyphenated keys, e.g. `max-size`
+        while matches!(stream.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '-') {
+            let mut ahead = stream.clone();
+            ahead.skip();
+
+            match ahead.next() {
+                Some(TokenTree::Ident(next)) => {
+                    key.push('-');
+                    key.push_str(&next.to_string());
+                    *stream = ahead;
+                },
+                _ => break,
+            }
+        }
+
         Ok(AttrNode {
-            // node ident
-            ident: stream.parse()?,
+            ident,
+            key,
 
             data: match stream.next() {
                 // marker - no data
@@ -24,7 +43,7 @@ impl AttrTokenParse for AttrNode {
                     // valued - equality
                     '=' => AttrTree::Valued {
                         equal: punct,
-                        value: stream.parse()?,
+                        value: AttrNode::parse_value(stream)?,
                     },
 
                     // container - tailfish
@@ -57,7 +76,7 @@ impl AttrTokenParse for AttrNode {
                     // container - grouped
                     Delimiter::Parenthesis => AttrTree::Container {
                         group: group.delimiter(),
-                        nodes: AttrTokenStream::new(group.stream()).parse()?,
+                        nodes: AttrTokenStream::new_spanned(group.stream(), group.span()).parse()?,
                         tailfish: false,
                     },
 
@@ -84,16 +103,29 @@ impl AttrTokenParse for AttrNode {
 impl AttrTokenParse for Vec<AttrNode> {
     fn parse(stream: &mut AttrTokenStream) -> Result<Self, KeftaTokenError> {
         let mut nodes = Vec::new();
+        let mut position = 0;
 
         // iterate over streams
         while stream.has_tokens() {
-            nodes.push(stream.parse::<AttrNode>()?);
+            // literals, or signed numbers (e.g. `-5`), are positional
+            let positional = match stream.peek() {
+                Some(TokenTree::Literal(_)) => true,
+                Some(TokenTree::Punct(punct)) => punct.as_char() == '-' || punct.as_char() == '+',
+                _ => false,
+            };
+
+            if positional {
+                nodes.push(AttrNode::parse_positional(stream, position)?);
+                position += 1;
+            } else {
+                nodes.push(stream.parse::<AttrNode>()?);
+            }
 
             // skip over separators
             if let Some(peek) = stream.peek() {
                 match peek {
                     TokenTree::Punct(punct) => if punct.as_char() == ',' {
-                        let _ = stream.skip();
+                        stream.skip();
                     },
                     _ => continue
                 }
@@ -107,8 +139,252 @@ impl AttrTokenParse for Vec<AttrNode> {
 }
 
 impl AttrNode {
+    /// parse a value, up to the next top-level comma
+    ///
+    /// commas within `<...>` and closure parameters are not counted,
+    /// e.g. `HashMap<K, V>` or `|a, b| a + b`
+    pub fn parse_value(stream: &mut AttrTokenStream) -> Result<TokenStream, KeftaTokenError> {
+        let mut value: Vec<TokenTree> = Vec::new();
+        let mut depth = 0usize;
+        let mut joint = None;
+        let mut closure = false;
+
+        while let Some(peek) = stream.peek().cloned() {
+            if let TokenTree::Punct(punct) = peek {
+                match punct.as_char() {
+                    ',' if depth == 0 && !closure => break,
+                    // generics follow a path, e.g. `Vec<u8>` or `parse::<u8>`
+                    '<' if follows_path(&value) && opens_generics(stream.clone()) => depth += 1,
+                    // closure parameters, optionally after `move` or `async`
+                    '|' if closure => closure = false,
+                    '|' if value.iter().all(|x| matches!(x, TokenTree::Ident(i) if i == "move" || i == "async")) =>
+                        closure = true,
+                    // ignore `->` and `=>`
+                    '>' if !matches!(joint, Some('-' | '=')) => depth = depth.saturating_sub(1),
+                    _ => ()
+                }
+
+                joint = match punct.spacing() {
+                    Spacing::Joint => Some(punct.as_char()),
+                    Spacing::Alone => None,
+                };
+            } else {
+                joint = None;
+            }
+
+            value.extend(stream.next());
+        }
+
+        if value.is_empty() {
+            return Err(match stream.next() {
+                None => KeftaTokenError::ExpectedToken { span: stream.stream_span() },
+                Some(found) => KeftaTokenError::Expected {
+                    expected: "a value",
+                    description: None,
+                    found
+                }
+            });
+        }
+
+        Ok(TokenStream::from_iter(value))
+    }
+
+    /// parse a positional node
+    pub fn parse_positional(stream: &mut AttrTokenStream, index: usize) -> Result<AttrNode, KeftaTokenError> {
+        let span = match stream.peek() {
+            Some(token_tree) => token_tree.span(),
+            None => stream.stream_span(),
+        };
+
+        Ok(AttrNode::new(
+            Ident::new(&format!("_{}", index), span),
+            AttrTree::Positional { index, value: AttrNode::parse_value(stream)? }
+        ))
+    }
+
+    /// parse the body of an attribute, e.g. `(...)` for `#[attr(...)]`
+    ///
+    /// the bare form (`#[attr]`) and name-value form (`#[attr = value]`)
+    /// give a single node with the ident `ROOT_KEY`
     pub fn parse_root(stream: &mut AttrTokenStream) -> Result<Vec<AttrNode>, KeftaTokenError> {
-        let group = stream.parse::<Group>()?;
-        AttrTokenParse::parse(&mut AttrTokenStream::new(group.stream()))
+        match stream.next() {
+            // list - #[attr(...)]
+            Some(TokenTree::Group(group)) =>
+                AttrTokenParse::parse(&mut AttrTokenStream::new_spanned(group.stream(), group.span())),
+
+            // bare - #[attr]
+            None => Ok(vec![AttrNode::new(
+                Ident::new(ROOT_KEY, stream.stream_span()),
+                AttrTree::Marker
+            )]),
+
+            // name-value - #[attr = value]
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                let span = match stream.peek() {
+                    Some(token_tree) => token_tree.span(),
+                    None => stream.stream_span(),
+                };
+                let value = AttrNode::parse_value(stream)?;
+
+                if let Some(token_tree) = stream.next() {
+                    return Err(KeftaTokenError::Expected {
+                        expected: "the end of the attribute",
+                        description: None,
+                        found: token_tree
+                    });
+                }
+
+                Ok(vec![AttrNode::new(
+                    Ident::new(ROOT_KEY, span),
+                    AttrTree::Valued { equal: punct, value }
+                )])
+            },
+
+            Some(token_tree) => Err(KeftaTokenError::Expected {
+                expected: "`(...)` or `= value`",
+                description: None,
+                found: token_tree
+            })
+        }
     }
-}
\ No newline at end of file
+}
+
+// check if the previous tokens end with a path, i.e. an ident or `::`
+fn follows_path(value: &[TokenTree]) -> bool {
+    match value {
+        [.., TokenTree::Ident(_)] => true,
+        [.., TokenTree::Punct(first), TokenTree::Punct(second)] =>
+            first.as_char() == ':' && first.spacing() == Spacing::Joint && second.as_char() == ':',
+        _ => false,
+    }
+}
+
+// check if the next `<` opens generics, rather than a comparison or shift (e.g. `a < b` or `1 << 4`),
+// by looking ahead for its closing `>`
+fn opens_generics(mut stream: AttrTokenStream) -> bool {
+    // `<<`, `<=` and `<<=`
+    if let Some(TokenTree::Punct(punct)) = stream.next() {
+        if punct.spacing() == Spacing::Joint {
+            if let Some(TokenTree::Punct(next)) = stream.peek() {
+                if next.as_char() == '<' || next.as_char() == '=' {
+                    return false;
+                }
+            }
+        }
+    }
+
+    let mut depth = 1usize;
+    let mut joint = None;
+
+    while let Some(token_tree) = stream.next() {
+        if let TokenTree::Punct(punct) = &token_tree {
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' if !matches!(joint, Some('-' | '=')) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return true;
+                    }
+                },
+                // the start of the next node, e.g. `a < b, c = 1`
+                ',' if depth == 1 => {
+                    let mut ahead = stream.clone();
+                    if let (Some(TokenTree::Ident(_)), Some(TokenTree::Punct(equal))) = (ahead.next(), ahead.next()) {
+                        if equal.as_char() == '=' && equal.spacing() == Spacing::Alone {
+                            return false;
+                        }
+                    }
+                },
+                _ => (),
+            }
+
+            joint = match punct.spacing() {
+                Spacing::Joint => Some(punct.as_char()),
+                Spacing::Alone => None,
+            };
+        } else {
+            joint = None;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro2::TokenStream;
+    use crate::node::{AttrNode, AttrTree};
+    use crate::token::AttrTokenStream;
+
+    // parse `e = <value>, f = 1`, returning the value of `e`
+    fn parse_value(value: &str) -> String {
+        let stream: TokenStream = format!("e = {}, f = 1", value).parse().unwrap();
+        let nodes: Vec<AttrNode> = AttrTokenStream::new(stream).parse().unwrap();
+
+        assert_eq!(nodes.len(), 2, "`{}` was not split from the next node", value);
+        assert_eq!(nodes[1].ident, "f");
+
+        match &nodes[0].data {
+            AttrTree::Valued { value, .. } => value.to_string(),
+            data => panic!("expected a value, found {:?}", data),
+        }
+    }
+
+    fn assert_value(value: &str) {
+        let expected = value.parse::<TokenStream>().unwrap().to_string();
+        assert_eq!(parse_value(value), expected);
+    }
+
+    #[test]
+    fn generics() {
+        assert_value("HashMap<K, V>");
+        assert_value("Vec<Vec<u8>>");
+        assert_value("Vec<HashMap<K, V>>");
+        assert_value("std::collections::HashMap<K, V>");
+        assert_value("parse::<u8, u16>()");
+        assert_value("Box<dyn Fn(u8, u8) -> u8>");
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_value("a < b");
+        assert_value("a <= b");
+        assert_value("a > b");
+        assert_value("a < b && c > d");
+    }
+
+    #[test]
+    fn shifts() {
+        assert_value("1 << 4");
+        assert_value("a << 4");
+        assert_value("a >> 4");
+    }
+
+    #[test]
+    fn closures() {
+        assert_value("|a, b| a + b");
+        assert_value("move |a, b| a < b");
+        assert_value("|a: u8| -> u8 { a }");
+    }
+
+    #[test]
+    fn hyphenated_keys() {
+        let stream: TokenStream = "max-size = 1, is-on, a-b-c(d-e), f, -1".parse().unwrap();
+        let nodes: Vec<AttrNode> = AttrTokenStream::new(stream).parse().unwrap();
+
+        let keys: Vec<&str> = nodes.iter().map(|node| node.key.as_str()).collect();
+        assert_eq!(keys, ["max-size", "is-on", "a-b-c", "f", "_0"]);
+        assert_eq!(nodes[0].ident, "max");
+
+        match &nodes[2].data {
+            AttrTree::Container { nodes, .. } => assert_eq!(nodes[0].key, "d-e"),
+            data => panic!("expected a container, found {:?}", data),
+        }
+    }
+
+    #[test]
+    fn arrows() {
+        assert_value("fn(u8) -> u8");
+        assert_value("Fn<(u8,)> -> u8");
+    }
+}
@@ -25,7 +25,7 @@ impl AttrTokenParse for Ident {
     fn parse(stream: &mut AttrTokenStream) -> Result<Self, KeftaTokenError> {
         match stream.parse::<TokenTree>()? {
             TokenTree::Ident(ident) => Ok(ident),
-            token_tree @ _ => Err(KeftaTokenError::Expected {
+            token_tree => Err(KeftaTokenError::Expected {
                 expected: "ident",
                 description: None,
                 found: token_tree
@@ -38,7 +38,7 @@ impl AttrTokenParse for Punct {
     fn parse(stream: &mut AttrTokenStream) -> Result<Self, KeftaTokenError> {
         match stream.parse::<TokenTree>()? {
             TokenTree::Punct(punct) => Ok(punct),
-            token_tree @ _ => Err(KeftaTokenError::Expected {
+            token_tree => Err(KeftaTokenError::Expected {
                 expected: "punct",
                 description: None,
                 found: token_tree
@@ -51,7 +51,7 @@ impl AttrTokenParse for Literal {
     fn parse(stream: &mut AttrTokenStream) -> Result<Self, KeftaTokenError> {
         match stream.parse::<TokenTree>()? {
             TokenTree::Literal(literal) => Ok(literal),
-            token_tree @ _ => Err(KeftaTokenError::Expected {
+            token_tree => Err(KeftaTokenError::Expected {
                 expected: "literal",
                 description: None,
                 found: token_tree
@@ -64,7 +64,7 @@ impl AttrTokenParse for Group {
     fn parse(stream: &mut AttrTokenStream) -> Result<Self, KeftaTokenError> {
         match stream.parse::<TokenTree>()? {
             TokenTree::Group(group) => Ok(group),
-            token_tree @ _ => Err(KeftaTokenError::Expected {
+            token_tree => Err(KeftaTokenError::Expected {
                 expected: "group",
                 description: None,
                 found: token_tree
@@ -4,6 +4,7 @@ use proc_macro2::{Span, TokenStream, TokenTree};
 use crate::error::KeftaTokenError;
 use crate::token::AttrTokenParse;
 
+#[derive(Clone)]
 pub struct AttrTokenStream {
     last_span: Span,
     tokens: Peekable<TokenStreamIter>,
@@ -25,10 +26,20 @@ impl AttrTokenStream {
         }
     }
 
+    /// create a stream, using the given span when the stream is empty
+    pub fn new_spanned(stream: TokenStream, span: Span) -> Self {
+        let mut this = Self::new(stream);
+        if !this.has_tokens() {
+            this.last_span = span;
+        }
+        this
+    }
+
     pub fn new_tree(tree: TokenTree) -> Self {
         Self::new(TokenStream::from(tree))
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Option<TokenTree> {
         self.tokens.next()
     }
@@ -50,6 +61,6 @@ impl AttrTokenStream {
     }
 
     pub fn stream_span(&self) -> Span {
-        self.last_span.clone()
+        self.last_span
     }
 }
\ No newline at end of file
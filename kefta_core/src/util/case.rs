@@ -5,6 +5,7 @@ use crate::node::{AttrNode};
 use crate::parse::AttrValue;
 
 /// a literal to modify an ident into a given case
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IdentCase {
     Lower,
     Upper,
@@ -40,8 +41,8 @@ impl AttrValue for IdentCase {
 impl IdentCase {
     pub fn caseify(&self, value: &str) -> String {
         match self {
-            Self::Lower => value.to_uppercase(),
-            Self::Upper => value.to_lowercase(),
+            Self::Lower => value.to_lowercase(),
+            Self::Upper => value.to_uppercase(),
 
             Self::Pascal => capitalise_case(segment_string(value), true),
             Self::Camel => capitalise_case(segment_string(value), false),
@@ -53,6 +54,7 @@ impl IdentCase {
 }
 
 /// a literal to modify a string literal into a given case
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StringCase {
     Lower,
     Upper,
@@ -96,8 +98,8 @@ impl AttrValue for StringCase {
 impl StringCase {
     pub fn caseify(&self, value: &str) -> String {
         match self {
-            Self::Lower => value.to_uppercase(),
-            Self::Upper => value.to_lowercase(),
+            Self::Lower => value.to_lowercase(),
+            Self::Upper => value.to_uppercase(),
 
             Self::Pascal => capitalise_case(segment_string(value), true),
             Self::Camel => capitalise_case(segment_string(value), false),
@@ -112,22 +114,31 @@ impl StringCase {
 }
 
 fn segment_string(value: &str) -> Vec<String> {
+    let chars = value.chars().collect::<Vec<char>>();
     let mut segements = Vec::new();
     let mut buffer = String::new();
 
-    for c in value.chars() {
+    for (index, c) in chars.iter().copied().enumerate() {
         if c == '_' || c == '-' {
-            segements.push(buffer.clone());
+            segements.push(buffer.to_lowercase());
             buffer.clear();
             continue
         }
 
+        // runs of uppercase are a single segment, ending before the start of the next word
+        // e.g. `HTTPServer` -> `http`, `server`
         if c.is_uppercase() {
-            segements.push(buffer.to_lowercase());
-            buffer.clear();
+            let previous = index.checked_sub(1).map(|index| chars[index]);
+            let next = chars.get(index + 1);
+
+            if !previous.is_some_and(char::is_uppercase) || next.is_some_and(|next| next.is_lowercase()) {
+                segements.push(buffer.to_lowercase());
+                buffer.clear();
+            }
         }
         buffer.push(c);
     }
+    segements.push(buffer.to_lowercase());
 
     segements.retain(|x| !x.is_empty());
     segements
@@ -137,12 +148,16 @@ fn capitalise_case(segments: Vec<String>, mut first: bool) -> String {
     let mut buffer = String::new();
 
     for mut segment in segments {
-        if !first { first = true; continue }
+        if !first {
+            first = true;
+            buffer.push_str(&segment);
+            continue
+        }
         segment.replace_range(
             0..1,
-            &segment
+            segment
                 .chars()
-                .nth(0)
+                .next()
                 .unwrap()
                 .to_ascii_uppercase()
                 .to_string()
@@ -157,4 +172,44 @@ fn capitalise_case(segments: Vec<String>, mut first: bool) -> String {
 fn delimiter_case(segments: Vec<String>, delimiter: &str, upper: bool) -> String {
     (if upper { str::to_uppercase } else { str::to_lowercase})
         (&segments.join(delimiter))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::{IdentCase, StringCase};
+
+    #[test]
+    fn lower_upper() {
+        assert_eq!(StringCase::Lower.caseify("MaxSize"), "maxsize");
+        assert_eq!(StringCase::Upper.caseify("max_size"), "MAX_SIZE");
+        assert_eq!(IdentCase::Lower.caseify("MaxSize"), "maxsize");
+        assert_eq!(IdentCase::Upper.caseify("max_size"), "MAX_SIZE");
+    }
+
+    #[test]
+    fn pascal_camel() {
+        assert_eq!(StringCase::Pascal.caseify("max_size"), "MaxSize");
+        assert_eq!(StringCase::Pascal.caseify("maxSize"), "MaxSize");
+        assert_eq!(StringCase::Camel.caseify("max_size"), "maxSize");
+        assert_eq!(StringCase::Camel.caseify("MaxSize"), "maxSize");
+        assert_eq!(StringCase::Camel.caseify("MAX_SIZE"), "maxSize");
+        assert_eq!(IdentCase::Camel.caseify("max"), "max");
+    }
+
+    #[test]
+    fn delimited() {
+        assert_eq!(StringCase::Snake.caseify("MaxSize"), "max_size");
+        assert_eq!(StringCase::Snake.caseify("MAX_SIZE"), "max_size");
+        assert_eq!(StringCase::UpperSnake.caseify("maxSize"), "MAX_SIZE");
+        assert_eq!(StringCase::Kebab.caseify("max_size"), "max-size");
+        assert_eq!(StringCase::UpperKebab.caseify("MaxSize"), "MAX-SIZE");
+    }
+
+    #[test]
+    fn acronyms() {
+        assert_eq!(StringCase::Snake.caseify("HTTPServer"), "http_server");
+        assert_eq!(StringCase::Snake.caseify("getHTTPResponse"), "get_http_response");
+        assert_eq!(StringCase::Snake.caseify("IO"), "io");
+        assert_eq!(StringCase::Pascal.caseify("HTTPServer"), "HttpServer");
+        assert_eq!(StringCase::Snake.caseify("Http2Server"), "http2_server");
+    }
+}
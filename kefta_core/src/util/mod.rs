@@ -6,7 +6,7 @@ pub mod case;
 #[cfg(feature = "syn")]
 mod syn;
 #[cfg(feature = "syn")]
-pub use self::syn::Syn;
+pub use self::syn::{Syn, SynStr};
 
 //pub mod named;
 
@@ -1,5 +1,4 @@
 use std::ops::Deref;
-use proc_macro2::{TokenStream};
 use crate::error::{KeftaError, KeftaResult};
 use crate::node::{AttrNode, AttrTree};
 use crate::parse::AttrValue;
@@ -23,17 +22,18 @@ impl<T: Parse> AsRef<T> for Syn<T> {
     }
 }
 
-/*impl<T: Parse> Into<T> for Syn<T> {
-    fn into(self) -> T {
+impl<T: Parse> Syn<T> {
+    /// unwrap the inner value
+    pub fn into_inner(self) -> T {
         self.0
     }
-}*/
+}
 
 impl<T: Parse> AttrValue for Syn<T> {
     fn parse(node: AttrNode) -> KeftaResult<Self> {
         match node.data {
-            AttrTree::Valued { value, .. } =>
-                match parse2::<T>(TokenStream::from(value)) {
+            AttrTree::Valued { value, .. } | AttrTree::Positional { value, .. } =>
+                match parse2::<T>(value) {
                     Ok(parse) => Ok(Syn(parse)),
                     Err(e) => Err(KeftaError::Syn(e))
                 },
@@ -42,6 +42,41 @@ impl<T: Parse> AttrValue for Syn<T> {
     }
 }
 
+/// a wrapper around any `syn` value, parsed from the contents of a string literal, e.g. `ty = "Vec<u8>"`.
+///
+/// the tokens of the value (and any errors) are spanned to the literal.
+pub struct SynStr<T: Parse>(pub(crate) T);
+
+impl<T: Parse> SynStr<T> {
+    /// unwrap the inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Parse> Deref for SynStr<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Parse> AsRef<T> for SynStr<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Parse> AttrValue for SynStr<T> {
+    fn parse(node: AttrNode) -> KeftaResult<Self> {
+        match <syn::LitStr as AttrValue>::parse(node)?.parse::<T>() {
+            Ok(parse) => Ok(SynStr(parse)),
+            Err(e) => Err(KeftaError::Syn(e))
+        }
+    }
+}
+
 macro_rules! attr_syn {
     ( $($ident: ident),* ) => {
 
@@ -56,4 +91,8 @@ macro_rules! attr_syn {
     };
 }
 
-attr_syn!(Expr, Lit, LitStr);
\ No newline at end of file
+// `syn::Ident` is `proc_macro2::Ident`, which is parsed as a token tree
+attr_syn!(
+    Expr, ExprClosure, Lit, LitStr, LitInt, LitBool,
+    Path, Type, Visibility, WherePredicate, Generics
+);
@@ -1,11 +1,64 @@
-use kefta_core::error::{KeftaResult};
+use syn::{parse_quote, Attribute, Expr, LitStr, Path};
+use kefta_core::error::{KeftaError, KeftaResult};
 use kefta_core::node::AttrNode;
-use kefta_core::structs::{AttrMap, AttrStruct};
+use kefta_core::parse::AttrValue;
+use kefta_core::structs::{AttrDuplicates, AttrMap, AttrParseNamed, AttrStruct};
+use kefta_core::util::case::StringCase;
+
+#[derive(Debug)]
+pub struct ItemAttr {
+    pub allow_unknown: bool,
+    pub bound: Option<LitStr>,
+    pub krate: Path,
+    pub rename_all: Option<StringCase>,
+    pub duplicates: Option<AttrDuplicates>,
+}
+
+impl AttrStruct for ItemAttr {
+    fn parse(nodes: Vec<AttrNode>) -> KeftaResult<Self> {
+        let mut map = AttrMap::new(nodes);
+
+        // `deny_unknown` is the default, but may be given explicitly
+        let allow_unknown = map.parse_one(&["allow_unknown"])?;
+        if let Some(node) = map.parse_optional::<AttrNode>(&["deny_unknown"])? {
+            let span = node.ident.span();
+            if <bool as AttrValue>::parse(node)? && allow_unknown {
+                return Err(KeftaError::Message {
+                    message: "`allow_unknown` and `deny_unknown` cannot be used together".to_string(),
+                    span: Some(span)
+                });
+            }
+        }
+
+        let attr = Self {
+            allow_unknown,
+            bound: map.parse_optional(&["bound"])?,
+            krate: match map.parse_optional::<LitStr>(&["crate"])? {
+                Some(krate) => krate.parse().map_err(KeftaError::Syn)?,
+                None => parse_quote!(::kefta),
+            },
+            rename_all: map.parse_optional(&["rename_all"])?,
+            duplicates: map.parse_optional(&["duplicates"])?,
+        };
+
+        map.deny_unknown()?;
+        Ok(attr)
+    }
+}
+
+impl ItemAttr {
+    /// parse the `#[attr(...)]` attributes of an item
+    pub fn parse_item(attrs: &[Attribute]) -> syn::Result<Self> {
+        Ok(attrs.parse_attrs_named("attr")?)
+    }
+}
 
 #[derive(Debug)]
 pub struct StructAttr {
     pub name: Option<String>,
     pub alias: Vec<String>,
+    pub position: Option<usize>,
+    pub root_value: bool,
 
     pub required: bool,
     pub optional: bool,
@@ -13,22 +66,48 @@ pub struct StructAttr {
     pub multiple: bool,
     pub container: bool,
 
-    pub with: Option<String>,
+    pub with: Option<Expr>,
+    pub parse_with: Option<Expr>,
+
+    pub default: Option<Expr>,
+    pub default_with: Option<Expr>,
+
+    pub skip: bool,
+    pub compute: Option<Expr>,
+    pub flatten: bool,
+    pub rest: bool,
+
+    pub bound: Option<LitStr>,
+    pub duplicates: Option<AttrDuplicates>,
 }
 
 impl AttrStruct for StructAttr {
     fn parse(nodes: Vec<AttrNode>) -> KeftaResult<Self> {
         let mut map = AttrMap::new(nodes);
 
-        Ok(Self {
+        let attr = Self {
             name: map.parse_optional(&["name"])?,
             alias: map.parse_array(&["alias", "names"])?,
+            position: map.parse_optional(&["position", "pos"])?,
+            root_value: map.parse_one(&["root_value", "root"])?,
             required: map.parse_one(&["required", "req"])?,
             optional: map.parse_one(&["optional", "opt"])?,
             multiple: map.parse_one(&["multiple", "many"])?,
             container: map.parse_one(&["container", "map"])?,
-            with: map.parse_one(&["with", "parse", "call"])?
-        })
+            with: map.parse_optional(&["with", "parse", "call"])?,
+            parse_with: map.parse_optional(&["parse_with"])?,
+            default: map.parse_optional(&["default"])?,
+            default_with: map.parse_optional(&["default_with"])?,
+            skip: map.parse_one(&["skip"])?,
+            compute: map.parse_optional(&["compute"])?,
+            flatten: map.parse_one(&["flatten"])?,
+            rest: map.parse_one(&["rest"])?,
+            bound: map.parse_optional(&["bound"])?,
+            duplicates: map.parse_optional(&["duplicates"])?,
+        };
+
+        map.deny_unknown()?;
+        Ok(attr)
     }
 }
 
@@ -41,9 +120,12 @@ impl AttrStruct for EnumAttr {
     fn parse(nodes: Vec<AttrNode>) -> KeftaResult<Self> {
         let mut map = AttrMap::new(nodes);
 
-        Ok(Self {
+        let attr = Self {
             name: map.parse_optional(&["name"])?,
             alias: map.parse_array(&["alias", "names"])?,
-        })
+        };
+
+        map.deny_unknown()?;
+        Ok(attr)
     }
 }
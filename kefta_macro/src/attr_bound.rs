@@ -0,0 +1,105 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{parse_quote, GenericArgument, Generics, Ident, LitStr, Path, PathArguments, Token, Type, WherePredicate};
+use syn::punctuated::Punctuated;
+use crate::attr::StructAttr;
+
+/// parse a `bound = "..."` string into where predicates
+pub fn attr_parse_bound(bound: &LitStr) -> syn::Result<Vec<WherePredicate>> {
+    let predicates = bound.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+    Ok(predicates.into_iter().collect())
+}
+
+/// infer the bounds required to parse a field, if its type uses any type parameters
+pub fn attr_field_bounds(ty: &Type, attrs: &StructAttr, params: &[Ident], krate: &Path) -> syn::Result<Vec<WherePredicate>> {
+    if let Some(bound) = &attrs.bound {
+        return attr_parse_bound(bound);
+    }
+
+    let used: Vec<&Ident> = params.iter()
+        .filter(|param| mentions(ty.to_token_stream(), param))
+        .collect();
+    if used.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let default = attrs.default.is_none() && attrs.default_with.is_none();
+
+    let bounds = if attrs.container && attrs.multiple {
+        let ty = vec_element(ty).unwrap_or(ty);
+        vec![parse_quote!( #ty: #krate::AttrStruct )]
+    } else if attrs.rest || attrs.container {
+        vec![parse_quote!( #ty: #krate::AttrStruct )]
+    } else if attrs.flatten {
+        vec![parse_quote!( #ty: #krate::AttrFlatten )]
+    } else if attrs.skip || attrs.compute.is_some() {
+        if default { vec![parse_quote!( #ty: ::core::default::Default )] } else { Vec::new() }
+    } else if attrs.with.is_some() {
+        Vec::new()
+    } else if attrs.parse_with.is_some() {
+        // the function parses the value, but `parse_or_with` may fall back to `Default`
+        if default && !(attrs.required || attrs.optional || attrs.multiple) {
+            vec![parse_quote!( #ty: ::core::default::Default )]
+        } else {
+            Vec::new()
+        }
+    } else {
+        let mut bounds: Vec<WherePredicate> = used.iter()
+            .map(|param| parse_quote!( #param: #krate::AttrValue ))
+            .collect();
+
+        // `parse_one` falls back to `Default`
+        if default && !(attrs.required || attrs.optional || attrs.multiple) {
+            bounds.push(parse_quote!( #ty: ::core::default::Default ));
+        }
+        bounds
+    };
+
+    Ok(bounds)
+}
+
+/// add the inferred (or overridden) bounds to the item generics
+pub fn attr_generics(mut generics: Generics, bound: Option<LitStr>, inferred: Vec<WherePredicate>) -> syn::Result<Generics> {
+    let predicates = match bound {
+        Some(bound) => attr_parse_bound(&bound)?,
+        None => inferred,
+    };
+
+    if !predicates.is_empty() {
+        generics.make_where_clause().predicates.extend(predicates);
+    }
+    Ok(generics)
+}
+
+/// the type parameter idents of the item generics
+pub fn attr_params(generics: &Generics) -> Vec<Ident> {
+    generics.type_params().map(|param| param.ident.clone()).collect()
+}
+
+// the element type of a `Vec<T>`
+fn vec_element(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Vec" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// check if a token stream contains an ident
+fn mentions(stream: TokenStream, ident: &Ident) -> bool {
+    stream.into_iter().any(|token| match token {
+        TokenTree::Ident(x) => &x == ident,
+        TokenTree::Group(group) => mentions(group.stream(), ident),
+        _ => false,
+    })
+}
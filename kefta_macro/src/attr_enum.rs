@@ -0,0 +1,160 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DataEnum, DeriveInput, Fields, Ident, LitStr, Generics, Variant};
+use syn::spanned::Spanned;
+use kefta_core::structs::AttrParseNamed;
+use kefta_core::util::case::StringCase;
+use crate::attr::{EnumAttr, ItemAttr, StructAttr};
+use crate::attr_bound::{attr_field_bounds, attr_generics, attr_params};
+use crate::attr_struct::{attr_fields, attr_finish, AttrFields, FieldOptions};
+
+pub fn attr_enum(input: DeriveInput) -> syn::Result<TokenStream> {
+    let data = if let Data::Enum(data) = input.data { data } else { unreachable!() };
+
+    let item = ItemAttr::parse_item(&input.attrs)?;
+
+    // unit-only enums are parsed as values, otherwise as tagged containers
+    if data.variants.iter().all(|variant| matches!(variant.fields, Fields::Unit)) {
+        attr_enum_value(input.ident, input.generics, data, item)
+    } else {
+        attr_enum_tagged(input.ident, input.generics, data, item)
+    }
+}
+
+fn attr_enum_value(ident: Ident, generics: Generics, data: DataEnum, item: ItemAttr) -> syn::Result<TokenStream> {
+    let krate = &item.krate;
+    let mut matches = TokenStream::new();
+    let mut names = Vec::new();
+
+    for variant in data.variants {
+        let ident = variant.ident.clone();
+        let name = match item.rename_all {
+            Some(case) => case.caseify(&ident.to_string()),
+            None => ident.to_string(),
+        };
+        let keys = attr_enum_keys(&variant, name)?;
+        let name = keys[0].clone();
+
+        matches.extend(quote! {
+            if variant.is(&[ #(#keys),* ]) {
+                return ::core::result::Result::Ok(Self::#ident);
+            }
+        });
+        names.push(name);
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #krate::AttrValue for #ident #ty_generics #where_clause {
+            fn parse(node: #krate::AttrNode) -> #krate::error::KeftaResult<Self> {
+                let variant = <#krate::AttrVariant as #krate::AttrValue>::parse(node)?;
+                #matches
+                ::core::result::Result::Err(variant.error(&[ #(#names),* ]))
+            }
+        }
+    })
+}
+
+fn attr_enum_tagged(ident: Ident, generics: Generics, data: DataEnum, item: ItemAttr) -> syn::Result<TokenStream> {
+    let mut variants = Vec::new();
+    let mut arms = TokenStream::new();
+    let mut bounds = Vec::new();
+    let finish = attr_finish(&item);
+    let krate = &item.krate;
+    let params = attr_params(&generics);
+    let options = FieldOptions { params: &params, krate, rename_all: None, duplicates: item.duplicates };
+
+    let case = item.rename_all.unwrap_or(StringCase::Snake);
+
+    for (index, variant) in data.variants.into_iter().enumerate() {
+        let keys = attr_enum_keys(&variant, case.caseify(&variant.ident.to_string()))?;
+        let keys = quote!( &[ #(#keys),* ] );
+        let ident = variant.ident;
+
+        let build = match variant.fields {
+            // marker variant
+            Fields::Unit => quote! {
+                errors.ok(map.parse_required::<()>(#keys)).map(|_| Self::#ident)
+            },
+
+            // inner structure
+            Fields::Unnamed(fields) => if fields.unnamed.len() == 1 {
+                let field = &fields.unnamed[0];
+                let attrs: StructAttr = field.attrs.as_slice().parse_attrs_named("attr")?;
+                bounds.extend(attr_field_bounds(&field.ty, &StructAttr { container: true, ..attrs }, &params, krate)?);
+                quote! { errors.ok(map.parse_container(#keys)).map(Self::#ident) }
+            } else {
+                return Err(syn::Error::new(fields.span(), "expected a single unnamed field"));
+            },
+
+            // inline structure
+            fields @ Fields::Named(_) => {
+                let span = fields.span();
+                let AttrFields { parse, construct, compute, keys: field_keys, flatten, bounds: field_bounds, .. } =
+                    attr_fields(quote!(Self::#ident), fields, &options)?;
+                bounds.extend(field_bounds);
+                if !compute.is_empty() {
+                    return Err(syn::Error::new(span, "computed fields are not supported in enum variants"));
+                }
+                if !flatten.is_empty() {
+                    return Err(syn::Error::new(span, "flattened fields are not supported in enum variants"));
+                }
+
+                quote! {
+                    match errors.ok(map.parse_container::<::std::vec::Vec<#krate::AttrNode>>(#keys)) {
+                        ::core::option::Option::Some(nodes) => {
+                            let mut map = #krate::AttrMap::new(nodes);
+                            map.know_keys(&#krate::AttrKeys::new(&[ #(#field_keys),* ], &[]));
+                            #parse
+                            #finish
+                            if errors.is_empty() {
+                                ::core::option::Option::Some(#construct)
+                            } else {
+                                ::core::option::Option::None
+                            }
+                        },
+                        ::core::option::Option::None => ::core::option::Option::None,
+                    }
+                }
+            }
+        };
+
+        arms.extend(quote! { #index => #build, });
+        variants.push(keys);
+    }
+
+    let generics = attr_generics(generics, item.bound, bounds)?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #krate::AttrStruct for #ident #ty_generics #where_clause {
+            fn parse(nodes: ::std::vec::Vec<#krate::AttrNode>) -> #krate::error::KeftaResult<Self> {
+                let mut map = #krate::AttrMap::new(nodes);
+                let mut errors = #krate::error::KeftaErrors::new();
+                let value = match map.select(&[ #(#variants),* ])? {
+                    #arms
+                    _ => ::core::unreachable!()
+                };
+                #finish
+                errors.finish()?;
+                ::core::result::Result::Ok(value.unwrap())
+            }
+        }
+    })
+}
+
+fn attr_enum_keys(variant: &Variant, default: String) -> syn::Result<Vec<LitStr>> {
+    let span = variant.ident.span();
+
+    // parse attrs
+    let attrs: EnumAttr = variant.attrs.as_slice().parse_attrs_named("attr")?;
+
+    // build key array
+    let mut keys = vec![LitStr::new(&attrs.name.unwrap_or(default), span)];
+    for alias in attrs.alias {
+        keys.push(LitStr::new(&alias, span));
+    }
+
+    Ok(keys)
+}
@@ -1,59 +1,343 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote};
-use syn::{Data, DeriveInput, Field, LitStr};
+use syn::{Data, DeriveInput, Expr, ExprLit, ExprPath, Field, Fields, GenericArgument, Ident, Index, Lit, LitStr, Member, Path, PathArguments, Type, WherePredicate};
+use syn::ext::IdentExt;
 use syn::spanned::Spanned;
-use kefta_core::structs::AttrParse;
-use crate::attr::StructAttr;
+use kefta_core::node::ROOT_KEY;
+use kefta_core::structs::{AttrDuplicates, AttrParseNamed};
+use kefta_core::util::case::StringCase;
+use crate::attr::{ItemAttr, StructAttr};
+use crate::attr_bound::{attr_field_bounds, attr_generics, attr_params};
 
 pub fn attr_struct(input: DeriveInput) -> syn::Result<TokenStream> {
     let data = if let Data::Struct(data) = input.data { data } else { unreachable!() };
+    let item = ItemAttr::parse_item(&input.attrs)?;
 
-    let mut constructor = TokenStream::new();
+    let params = attr_params(&input.generics);
+    let krate = &item.krate;
+    let options = FieldOptions { params: &params, krate, rename_all: item.rename_all, duplicates: item.duplicates };
+    let AttrFields { parse, construct, compute, keys, flatten, rest, bounds } =
+        attr_fields(quote!(Self), data.fields, &options)?;
 
-    for field in data.fields {
-        constructor.extend(attr_struct_field(field));
-    }
+    let ident = input.ident;
+
+    // check for key collisions without building, generic structs are checked once used
+    let check = if input.generics.params.is_empty() {
+        quote! {
+            const _: () = {
+                let _ = <#ident as #krate::AttrFlatten>::KEYS;
+            };
+        }
+    } else {
+        TokenStream::new()
+    };
 
-    let (ident, generics) = (input.ident, input.generics);
+    let generics = attr_generics(input.generics, item.bound.clone(), bounds)?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let finish = attr_finish(&item);
+    let with_rest = if rest { quote!(.with_rest()) } else { TokenStream::new() };
+
+    // computed fields are filled in once everything else is parsed
+    let build = if compute.is_empty() {
+        quote! { ::core::result::Result::Ok(#construct) }
+    } else {
+        quote! {
+            let mut value = #construct;
+            #compute
+            ::core::result::Result::Ok(value)
+        }
+    };
 
     Ok(quote! {
-        impl #generics kefta::AttrStruct for #ident #generics {
-            fn parse(nodes: Vec<kefta::AttrNode>) -> kefta::error::KeftaResult<Self> {
-                let mut map = kefta::AttrMap::new(nodes);
-                Ok(Self {
-                    #constructor
-                })
+        #check
+
+        impl #impl_generics #krate::AttrFlatten for #ident #ty_generics #where_clause {
+            const KEYS: #krate::AttrKeys = #krate::AttrKeys::new(
+                &[ #(#keys),* ],
+                &[ #(&<#flatten as #krate::AttrFlatten>::KEYS),* ],
+            )#with_rest;
+
+            fn parse_map(map: &mut #krate::AttrMap) -> #krate::error::KeftaResult<Self> {
+                // evaluate the keys, checking for collisions
+                let _ = <Self as #krate::AttrFlatten>::KEYS;
+
+                let mut errors = #krate::error::KeftaErrors::new();
+                #parse
+                errors.finish()?;
+                #build
+            }
+        }
+
+        impl #impl_generics #krate::AttrStruct for #ident #ty_generics #where_clause {
+            fn parse(nodes: ::std::vec::Vec<#krate::AttrNode>) -> #krate::error::KeftaResult<Self> {
+                let map = &mut #krate::AttrMap::new(nodes);
+                map.know_keys(&<Self as #krate::AttrFlatten>::KEYS);
+                let mut errors = #krate::error::KeftaErrors::new();
+                let value = errors.ok(<Self as #krate::AttrFlatten>::parse_map(map));
+                #finish
+                errors.finish()?;
+                ::core::result::Result::Ok(value.unwrap())
             }
         }
     })
 }
 
-fn attr_struct_field(field: Field) -> syn::Result<TokenStream> {
-    // parse ident
-    let ident = if let Some(ident) = field.ident {
-        ident
+/// build the checks run after all fields are parsed
+pub fn attr_finish(item: &ItemAttr) -> TokenStream {
+    if item.allow_unknown {
+        TokenStream::new()
+    } else {
+        quote! { errors.ok(map.deny_unknown()); }
+    }
+}
+
+/// the parsing of a set of fields
+pub struct AttrFields {
+    /// statements parsing each field, collecting errors into `errors`
+    pub parse: TokenStream,
+    /// the constructor, only valid if no errors were collected
+    pub construct: TokenStream,
+    /// statements assigning computed fields on the constructed `value`
+    pub compute: TokenStream,
+    /// the keys used by the fields
+    pub keys: Vec<LitStr>,
+    /// the types of flattened fields
+    pub flatten: Vec<Type>,
+    /// if a field takes all remaining nodes
+    pub rest: bool,
+    /// the bounds required by the fields
+    pub bounds: Vec<WherePredicate>,
+}
+
+/// options for the parsing of a set of fields, from the item
+pub struct FieldOptions<'a> {
+    /// the type parameters of the item
+    pub params: &'a [Ident],
+    /// the path to the `kefta` crate
+    pub krate: &'a Path,
+    /// the case of field keys
+    pub rename_all: Option<StringCase>,
+    /// the default duplicate policy of fields
+    pub duplicates: Option<AttrDuplicates>,
+}
+
+pub fn attr_fields(path: TokenStream, fields: Fields, options: &FieldOptions) -> syn::Result<AttrFields> {
+    let krate = options.krate;
+    let mut parse = TokenStream::new();
+    let mut constructor = TokenStream::new();
+    let mut compute = TokenStream::new();
+    let mut keys = Vec::new();
+    let mut flatten = Vec::new();
+    let mut rest = TokenStream::new();
+    let mut bounds = Vec::new();
+
+    let named = matches!(fields, Fields::Named(_));
+
+    // skipped fields do not take up a position
+    let mut position = 0;
+
+    for (index, field) in fields.into_iter().enumerate() {
+        let local = format_ident!("__field{}", index);
+        let ident = field.ident.clone();
+        let span = attr_field_span(&field);
+
+        // parse attrs
+        let attrs: StructAttr = field.attrs.as_slice().parse_attrs_named("attr")?;
+        bounds.extend(attr_field_bounds(&field.ty, &attrs, options.params, krate)?);
+        let duplicates = attrs.duplicates.or(options.duplicates);
+
+        if attrs.rest {
+            attr_lone_field(&attrs, span, "a rest field")?;
+            let ty = field.ty;
+            rest.extend(quote! { let #local = errors.ok(<#ty as #krate::AttrStruct>::parse(map.drain())); });
+        } else if attrs.flatten {
+            attr_lone_field(&attrs, span, "a flattened field")?;
+            let ty = field.ty;
+            let func = attr_duplicates(quote! { <#ty as #krate::AttrFlatten>::parse_map(map) }, duplicates, krate);
+            parse.extend(quote! { let #local = errors.ok(#func); });
+            flatten.push(ty);
+        } else if attrs.skip || attrs.compute.is_some() {
+            let (default, compute_with) = attr_skip_field(attrs, span)?;
+            parse.extend(quote! { let #local = ::core::option::Option::Some(#default); });
+
+            if let Some(compute_with) = compute_with {
+                let member = match &ident {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(index)),
+                };
+                compute.extend(quote! { value.#member = #compute_with(&value)?; });
+            }
+        } else {
+            let field_keys = attr_field_keys(&field, &attrs, position, span, options.rename_all);
+            let root_value = attrs.root_value;
+            let mut func = attr_struct_func(attrs, field_keys.clone(), span)?;
+
+            // a bare root (e.g. `#[attr]`) is only taken by marker fields
+            if root_value && !attr_marker_type(&field.ty) {
+                func = quote! { map.without_bare_root(|map| #func) };
+            }
+
+            let func = attr_duplicates(func, duplicates, krate);
+            keys.extend(field_keys);
+            position += 1;
+
+            parse.extend(quote! { let #local = errors.ok(#func); });
+        }
+
+        constructor.extend(match ident {
+            Some(ident) => quote!( #ident: #local.unwrap(), ),
+            None => quote!( #local.unwrap(), ),
+        });
+    }
+
+    // leftover nodes are taken once all other fields are parsed
+    let has_rest = !rest.is_empty();
+    parse.extend(rest);
+
+    let construct = if named {
+        quote!( #path { #constructor } )
+    } else if constructor.is_empty() {
+        path
     } else {
-        return Err(syn::Error::new(field.span(), "expected a named field"));
+        quote!( #path( #constructor ) )
     };
 
-    // parse attrs
-    let attrs: StructAttr = match field.attrs.parse_attrs() {
-        Ok(attr) => attr,
-        Err(e) => return Err(e.into())
+    Ok(AttrFields { parse, construct, compute, keys, flatten, rest: has_rest, bounds })
+}
+
+/// run the parsing of a field with a duplicate policy, if any
+fn attr_duplicates(func: TokenStream, duplicates: Option<AttrDuplicates>, krate: &Path) -> TokenStream {
+    let policy = match duplicates {
+        None => return func,
+        Some(AttrDuplicates::Error) => quote!(Error),
+        Some(AttrDuplicates::First) => quote!(First),
+        Some(AttrDuplicates::Last) => quote!(Last),
     };
+    quote! { map.with_duplicates(#krate::AttrDuplicates::#policy, |map| #func) }
+}
 
-    //println!("{:?}", attrs);
+/// check if a type is parsed from a marker, i.e. `bool` or `()`, optionally within an `Option`
+fn attr_marker_type(ty: &Type) -> bool {
+    match ty {
+        Type::Tuple(tuple) => tuple.elems.is_empty(),
+        Type::Paren(paren) => attr_marker_type(&paren.elem),
+        Type::Group(group) => attr_marker_type(&group.elem),
+        Type::Path(path) if path.qself.is_none() => match path.path.segments.last() {
+            Some(segment) if segment.ident == "bool" => true,
+            Some(segment) if segment.ident == "Option" => match &segment.arguments {
+                PathArguments::AngleBracketed(args) => match args.args.first() {
+                    Some(GenericArgument::Type(ty)) if args.args.len() == 1 => attr_marker_type(ty),
+                    _ => false,
+                },
+                _ => false,
+            },
+            _ => false,
+        },
+        _ => false,
+    }
+}
 
-    // build key array
-    let mut keys = if let Some(rename) = attrs.name {
-        vec![LitStr::new(&rename, ident.span())]
-    } else {
-        vec![LitStr::new(&ident.to_string(), ident.span())]
+fn attr_field_span(field: &Field) -> Span {
+    match &field.ident {
+        Some(ident) => ident.span(),
+        None => field.span(),
+    }
+}
+
+/// check a flattened or rest field has no other options
+fn attr_lone_field(attrs: &StructAttr, span: Span, kind: &str) -> syn::Result<()> {
+    if (attrs.flatten && attrs.rest) || attrs.required || attrs.optional || attrs.multiple || attrs.container
+        || attrs.with.is_some() || attrs.parse_with.is_some() || attrs.position.is_some() || attrs.root_value || attrs.name.is_some() || !attrs.alias.is_empty()
+        || attrs.default.is_some() || attrs.default_with.is_some() || attrs.skip || attrs.compute.is_some()
+    {
+        return Err(syn::Error::new(span, format!("{} cannot have any other options.", kind)));
+    }
+    Ok(())
+}
+
+/// build the value of a skipped or computed field, and the function computing it
+fn attr_skip_field(attrs: StructAttr, span: Span) -> syn::Result<(TokenStream, Option<ExprPath>)> {
+    if attrs.required || attrs.optional || attrs.multiple || attrs.container || attrs.with.is_some()
+        || attrs.parse_with.is_some() || attrs.position.is_some() || attrs.root_value
+    {
+        return Err(syn::Error::new(
+            span,
+            "a skipped or computed field cannot be `required`, `optional`, `multiple`, `container`, \
+            `with`, `parse_with`, `position` or `root_value`."
+        ));
+    }
+
+    let compute = match attrs.compute {
+        Some(expr) => Some(attr_func_path(expr)?),
+        None => None,
     };
-    for alias in attrs.alias {
-        keys.push(LitStr::new(&alias, ident.span()));
+
+    let default = attr_default(attrs.default, attrs.default_with, span)?
+        .unwrap_or_else(|| quote! { ::core::default::Default::default() });
+
+    Ok((default, compute))
+}
+
+/// parse a path to a function, given as a path or a string literal
+fn attr_func_path(expr: Expr) -> syn::Result<ExprPath> {
+    match expr {
+        Expr::Path(path) => Ok(path),
+        Expr::Lit(ExprLit { lit: Lit::Str(lit), .. }) => lit.parse(),
+        expr => Err(syn::Error::new(expr.span(), "expected a path to a function")),
     }
+}
+
+/// build the default value expression of a field, if any
+fn attr_default(default: Option<Expr>, default_with: Option<Expr>, span: Span) -> syn::Result<Option<TokenStream>> {
+    Ok(match (default, default_with) {
+        (Some(Expr::Lit(ExprLit { lit: Lit::Str(lit), .. })), None) =>
+            Some(quote! { ::core::convert::Into::into(#lit) }),
+        (Some(expr), None) => Some(quote! { #expr }),
+        (None, Some(expr)) => {
+            let path = attr_func_path(expr)?;
+            Some(quote! { #path() })
+        },
+        (Some(_), Some(_)) => return Err(syn::Error::new(
+            span,
+            "attribute cannot have both a `default` and `default_with`."
+        )),
+        (None, None) => None,
+    })
+}
 
+fn attr_field_keys(field: &Field, attrs: &StructAttr, index: usize, span: Span, rename_all: Option<StringCase>) -> Vec<LitStr> {
+    // build key array
+    let mut keys = Vec::new();
+    match &field.ident {
+        // named fields, optionally positional
+        Some(ident) => {
+            if let Some(position) = attrs.position {
+                keys.push(LitStr::new(&format!("_{}", position), span));
+            }
+            if let Some(rename) = &attrs.name {
+                keys.push(LitStr::new(rename, span));
+            } else {
+                let ident = ident.unraw().to_string();
+                match rename_all {
+                    Some(case) => keys.push(LitStr::new(&case.caseify(&ident), span)),
+                    None => keys.push(LitStr::new(&ident, span)),
+                }
+            }
+        },
+        // unnamed fields, always positional
+        None => keys.push(LitStr::new(&format!("_{}", attrs.position.unwrap_or(index)), span)),
+    }
+    for alias in &attrs.alias {
+        keys.push(LitStr::new(alias, span));
+    }
+    if attrs.root_value {
+        keys.push(LitStr::new(ROOT_KEY, span));
+    }
+
+    keys
+}
+
+fn attr_struct_func(attrs: StructAttr, keys: Vec<LitStr>, span: Span) -> syn::Result<TokenStream> {
     // build key tokens
     let mut key_arr = TokenStream::new();
     let key_len = keys.len() - 1;
@@ -66,39 +350,82 @@ fn attr_struct_field(field: Field) -> syn::Result<TokenStream> {
     }
     let keys = quote!( &[ #key_arr ] );
 
+    // build default, only evaluated if not present
+    let default = attr_default(attrs.default, attrs.default_with, span)?
+        .map(|default| quote! { || #default });
+
+    if default.is_some() && (attrs.with.is_some() || attrs.container || attrs.required || attrs.optional) {
+        return Err(syn::Error::new(
+            span,
+            "a default cannot be used with `required`, `optional`, `container` or `with`."
+        ));
+    }
+
+    if attrs.parse_with.is_some() && (attrs.with.is_some() || attrs.container) {
+        return Err(syn::Error::new(
+            span,
+            "`parse_with` cannot be used with `container` or `with`."
+        ));
+    }
+
+    // per node parsing function
+    let (suffix, parse_with) = match attrs.parse_with {
+        Some(parse_with) => {
+            let parse_with = attr_func_path(parse_with)?;
+            ("_with", quote! { , #parse_with })
+        },
+        None => ("", TokenStream::new()),
+    };
+    let method = |name: &str| format_ident!("{}{}", name, suffix);
 
     let func = if let Some(call) = attrs.with {
-        let call_ident = format_ident!("{}", call);
-        quote! { map.parse_with(#keys, #call_ident) }
+        let call = attr_func_path(call)?;
+        quote! { map.parse_with(#keys, #call) }
     } else if attrs.container {
-        quote! { map.parse_container(#keys) }
+        if attrs.multiple {
+            quote! { map.parse_container_array(#keys) }
+        } else {
+            quote! { map.parse_container(#keys) }
+        }
     } else {
         match (attrs.required, attrs.optional) {
 
-            (false, false) => if attrs.multiple {
-                quote! { map.parse_array(#keys) }
-            } else {
-                quote! { map.parse_one(#keys) }
+            (false, false) => match (attrs.multiple, default) {
+                (true, Some(default)) => {
+                    let method = method("parse_array_or");
+                    quote! { map.#method(#keys #parse_with, #default) }
+                },
+                (true, None) => {
+                    let method = method("parse_array");
+                    quote! { map.#method(#keys #parse_with) }
+                },
+                (false, Some(default)) => {
+                    let method = method("parse_or");
+                    quote! { map.#method(#keys #parse_with, #default) }
+                },
+                (false, None) => if parse_with.is_empty() {
+                    quote! { map.parse_one(#keys) }
+                } else {
+                    quote! { map.parse_or_with(#keys #parse_with, ::core::default::Default::default) }
+                },
             },
 
-            (true, false) => if attrs.multiple {
-                quote! { map.parse_array_required(#keys) }
-            } else {
-                quote! { map.parse_required(#keys) }
+            (true, false) => {
+                let method = method(if attrs.multiple { "parse_array_required" } else { "parse_required" });
+                quote! { map.#method(#keys #parse_with) }
             },
 
-            (false, true) => if attrs.multiple {
-                quote! { map.parse_array_optional(#keys) }
-            } else {
-                quote! { map.parse_optional(#keys) }
+            (false, true) => {
+                let method = method(if attrs.multiple { "parse_array_optional" } else { "parse_optional" });
+                quote! { map.#method(#keys #parse_with) }
             },
 
             (true, true) => return Err(syn::Error::new(
-                ident.span(),
+                span,
                 "attribute cannot be optional and required."
             ))
         }
     };
 
-    Ok(quote!( #ident: #func ?, ))
+    Ok(func)
 }
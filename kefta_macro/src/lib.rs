@@ -1,5 +1,7 @@
 mod attr;
 mod attr_struct;
+mod attr_enum;
+mod attr_bound;
 
 use proc_macro::TokenStream;
 use syn::{Data, parse_macro_input};
@@ -12,7 +14,7 @@ pub fn attr_macro(input: TokenStream) -> TokenStream {
 
     let out = match input.data {
         Data::Struct(_) => attr_struct::attr_struct(input),
-        Data::Enum(_) => Err(syn::Error::new(input.span(), "enums are not supported")),
+        Data::Enum(_) => attr_enum::attr_enum(input),
         Data::Union(_) => Err(syn::Error::new(input.span(), "unions are not supported")),
     };
 
@@ -9,7 +9,7 @@ use kefta::{Attr, parse_attr};
 pub fn test_macro(item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
 
-    let attrs = parse_attr!(input.attrs => MyAttr);
+    let attrs = parse_attr!(input.attrs, "test" => MyAttr);
     println!("ATTR {:?}", attrs);
 
     TokenStream::new()
@@ -17,11 +17,14 @@ pub fn test_macro(item: TokenStream) -> TokenStream {
 
 #[derive(Attr, Debug)]
 struct MyAttr {
+    /// a required value
     #[attr(required)]
     foo: u8,
     bar: bool,
     #[attr(container)]
-    baz: MyAttrBaz
+    baz: MyAttrBaz,
+    #[attr(optional)]
+    mode: Option<MyMode>,
 }
 
 #[derive(Attr, Debug)]
@@ -29,4 +32,11 @@ struct MyAttrBaz {
     #[attr(optional)]
     alpha: Option<String>,
     beta: bool
+}
+
+#[derive(Attr, Debug)]
+enum MyMode {
+    Fast,
+    #[attr(alias="snail")]
+    Slow
 }
\ No newline at end of file
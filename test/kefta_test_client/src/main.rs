@@ -6,9 +6,10 @@ fn main() {
     println!("Hello, world!");
 }
 
+/// a test struct
 #[derive(TestMacro)]
 #[test(baz::alpha = "Test!", baz(beta))]
-#[test(foo=69)]
+#[test(foo=69, mode=fast)]
 pub struct MyStruct {
     foo: String,
 }
\ No newline at end of file